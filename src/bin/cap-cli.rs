@@ -0,0 +1,458 @@
+//! Headless solver, for running batch experiments without the GUI.
+//!
+//! Either describe the board with flags:
+//! `cap-cli --width 5 --height 6 --start 1,1 --disable 3,3 --alg brute-force`
+//! or pass a JSON file with the same information (see `--dump-input` for the format):
+//! `cap-cli --input board.json --format json`
+//!
+//! Exits with status 3 when there is no tour and 4 when the search gave up before finding one,
+//! so scripts can tell a failed search apart from bad input, which exits with 1 or 2
+//!
+//! `--format notation` prints tours as squares like `a1 b3 c1`, see [cap_solver::solver::notation]
+//!
+//! `--export tour.svg` or `--export tour.png` also draws the board and the tour found:
+//! `cap-cli --width 6 --height 6 --export tour.png`
+//! and `--export moves.dot` or `--export moves.graphml` writes the move graph with the tour highlighted,
+//! see [cap_solver::solver::move_graph]
+//!
+//! `--enumerate open` or `--enumerate closed` finds every tour instead of only the first:
+//! `cap-cli --width 3 --height 10 --enumerate closed --count-only`
+
+use std::{
+	ops::ControlFlow,
+	path::{Path, PathBuf},
+	process::ExitCode,
+	str::FromStr,
+	time::Duration,
+};
+
+use anyhow::{bail, Context};
+use cap_solver::{
+	diagram::{self, render_png, Diagram},
+	solver::{
+		algs::{
+			enumerate_tours, Algorithm, Computation, ComputeHandle, OwnedComputeInput, PruningRules,
+			TourKind,
+		},
+		move_graph::{to_dot, to_graphml},
+		notation::write_tour,
+		pieces::StandardPieces,
+		BoardOptions, ChessPoint, Moves, Topology,
+	},
+};
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser, Debug)]
+#[command(about = "Solve a CAP board without opening the GUI")]
+struct Args {
+	/// JSON file describing the board, piece, start, algorithm and safety cap.
+	/// When given, the board flags below are ignored
+	#[arg(long)]
+	input: Option<PathBuf>,
+
+	#[arg(long, default_value_t = 8)]
+	width: u16,
+
+	#[arg(long, default_value_t = 8)]
+	height: u16,
+
+	/// Starting square as `row,column`, 1 indexed
+	#[arg(long, value_parser = parse_point, default_value = "1,1")]
+	start: ChessPoint,
+
+	/// Cells to disable, as `row,column`
+	#[arg(long, value_parser = parse_point)]
+	disable: Vec<ChessPoint>,
+
+	/// Cells to eliminate, as `row,column`
+	#[arg(long, value_parser = parse_point)]
+	eliminate: Vec<ChessPoint>,
+
+	/// Cells the tour must finish on, as `row,column`
+	#[arg(long, value_parser = parse_point)]
+	target: Vec<ChessPoint>,
+
+	/// How the edges of the board are joined
+	#[arg(long, value_enum, default_value_t = TopologyArg::Flat)]
+	topology: TopologyArg,
+
+	/// `knight`, a fairy piece (`camel`, `zebra`, `giraffe`, `wazir`, `ferz`, `king`, `nightrider`,
+	/// `rook`, `bishop`, `queen`, `amazon`, `archbishop`, `chancellor`), or `ab:A,B` for an AB knight
+	#[arg(long, value_parser = StandardPieces::from_str, default_value = "knight")]
+	piece: StandardPieces,
+
+	/// Id of a registered solver, see `--list-algs`
+	#[arg(long, default_value_t = Algorithm::default().id().to_string())]
+	alg: String,
+
+	/// Maximum number of states explored before giving up, 0 for no limit
+	#[arg(long, default_value_t = 1_000_000)]
+	safety_cap: u128,
+
+	/// Maximum number of seconds spent before giving up
+	#[arg(long)]
+	time_budget: Option<f64>,
+
+	/// Turn off the brute force pruning rules
+	#[arg(long)]
+	no_pruning: bool,
+
+	#[arg(long, value_enum, default_value_t = Format::Moves)]
+	format: Format,
+
+	/// Find every tour of this kind instead of only the first, ignoring `--alg`.
+	/// Each tour is printed as it is found, then a summary
+	#[arg(long, value_enum)]
+	enumerate: Option<EnumerateKind>,
+
+	/// With `--enumerate`, only print the summary
+	#[arg(long)]
+	count_only: bool,
+
+	/// Also draw the board and the tour found into this `.svg` or `.png` file,
+	/// or write the move graph to this `.dot` or `.graphml` file
+	#[arg(long)]
+	export: Option<PathBuf>,
+
+	/// How many times larger an `--export`ed PNG is than the SVG
+	#[arg(long, default_value_t = 2.)]
+	export_scale: f32,
+
+	/// Print the input as JSON instead of solving, useful as a template for `--input`
+	#[arg(long)]
+	dump_input: bool,
+
+	/// Print the ids of all available algorithms and exit
+	#[arg(long)]
+	list_algs: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+	/// Prints the [Computation] as JSON
+	Json,
+	/// Prints one move per line, and a summary to stderr
+	Moves,
+	/// Prints the squares visited like `a1 b3 c1`, and a summary to stderr
+	Notation,
+}
+
+impl Format {
+	fn print_tour(self, moves: &Moves) {
+		match self {
+			Format::Json => println!("{}", serde_json::to_string(moves).unwrap()),
+			Format::Moves => print!("{}", moves),
+			Format::Notation => println!("{}", write_tour(moves)),
+		}
+	}
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum EnumerateKind {
+	Open,
+	Closed,
+}
+
+impl From<EnumerateKind> for TourKind {
+	fn from(kind: EnumerateKind) -> Self {
+		match kind {
+			EnumerateKind::Open => TourKind::Open,
+			EnumerateKind::Closed => TourKind::Closed,
+		}
+	}
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum TopologyArg {
+	Flat,
+	Cylinder,
+	Torus,
+	Mobius,
+}
+
+impl From<TopologyArg> for Topology {
+	fn from(topology: TopologyArg) -> Self {
+		match topology {
+			TopologyArg::Flat => Topology::Flat,
+			TopologyArg::Cylinder => Topology::Cylinder,
+			TopologyArg::Torus => Topology::Torus,
+			TopologyArg::Mobius => Topology::Mobius,
+		}
+	}
+}
+
+/// Everything needed to run a computation, as read from `--input`
+#[derive(Serialize, Deserialize, Debug)]
+struct Input {
+	board_options: BoardOptions,
+	#[serde(default)]
+	piece: StandardPieces,
+	start: ChessPoint,
+	alg: String,
+	/// Missing for no limit
+	safety_cap: Option<u128>,
+	/// In seconds, missing for no limit
+	#[serde(default)]
+	time_budget: Option<f64>,
+	#[serde(default)]
+	pruning: PruningRules,
+}
+
+fn parse_point(s: &str) -> Result<ChessPoint, String> {
+	let (row, column) = s
+		.split_once(',')
+		.ok_or_else(|| format!("Expected `row,column`, got {:?}", s))?;
+	let row = row.trim().parse().map_err(|e| format!("Bad row: {}", e))?;
+	let column = column.trim().parse().map_err(|e| format!("Bad column: {}", e))?;
+	Ok(ChessPoint::new(row, column))
+}
+
+impl Args {
+	fn into_input(self) -> anyhow::Result<Input> {
+		if let Some(path) = &self.input {
+			let json = std::fs::read_to_string(path)
+				.with_context(|| format!("Couldn't read {}", path.display()))?;
+			return serde_json::from_str(&json)
+				.with_context(|| format!("Couldn't parse {}", path.display()));
+		}
+
+		let mut board_options = BoardOptions::new(self.width, self.height);
+		board_options.set_topology(self.topology.into());
+		let check = |board_options: &BoardOptions, p: &ChessPoint| {
+			if !board_options.validate_point(p) {
+				bail!("Cell {} is not on the {}x{} board", p, self.width, self.height);
+			}
+			Ok(())
+		};
+		for p in &self.disable {
+			check(&board_options, p)?;
+			board_options.rm(*p);
+		}
+		for p in &self.eliminate {
+			check(&board_options, p)?;
+			board_options.eliminate(p);
+		}
+		for p in &self.target {
+			check(&board_options, p)?;
+			board_options.toggle_target(*p);
+		}
+
+		Ok(Input {
+			board_options,
+			piece: self.piece,
+			start: self.start,
+			alg: self.alg,
+			safety_cap: (self.safety_cap != 0).then_some(self.safety_cap),
+			time_budget: self.time_budget,
+			pruning: if self.no_pruning {
+				PruningRules::NONE
+			} else {
+				PruningRules::default()
+			},
+		})
+	}
+}
+
+impl TryFrom<Input> for OwnedComputeInput {
+	type Error = anyhow::Error;
+
+	fn try_from(input: Input) -> Result<Self, Self::Error> {
+		let Some(alg) = Algorithm::from_id(&input.alg) else {
+			bail!("No algorithm with id {:?}, try --list-algs", input.alg);
+		};
+		if !input.board_options.is_available(&input.start) {
+			bail!("Start {} is not an available cell", input.start);
+		}
+		let time_budget = input
+			.time_budget
+			.map(Duration::try_from_secs_f64)
+			.transpose()
+			.context("Bad time budget")?;
+		if input.safety_cap.is_none() && time_budget.is_none() {
+			bail!("Without a safety cap or time budget the search might never finish");
+		}
+		Ok(OwnedComputeInput {
+			alg,
+			safety_cap: input.safety_cap,
+			time_budget,
+			pruning: input.pruning,
+			start: input.start,
+			board_options: input.board_options,
+			piece: input.piece.into(),
+		})
+	}
+}
+
+/// See the module docs for what the [ExitCode]s mean
+const NO_TOUR: u8 = 3;
+const GIVEN_UP: u8 = 4;
+
+fn main() -> anyhow::Result<ExitCode> {
+	let args = Args::parse();
+
+	if args.list_algs {
+		for alg in Algorithm::all() {
+			println!("{}\t{}", alg.id(), alg);
+		}
+		return Ok(ExitCode::SUCCESS);
+	}
+
+	let format = args.format;
+	let dump_input = args.dump_input;
+	let enumerate = args.enumerate;
+	let count_only = args.count_only;
+	let export = args.export.clone();
+	let export_scale = args.export_scale;
+	let input = args.into_input()?;
+
+	if dump_input {
+		println!("{}", serde_json::to_string_pretty(&input)?);
+		return Ok(ExitCode::SUCCESS);
+	}
+
+	let input = OwnedComputeInput::try_from(input)?;
+	if let Some(kind) = enumerate {
+		if export.is_some() {
+			bail!("--export shows a single tour, so can't be used with --enumerate");
+		}
+		enumerate_all(&input, kind.into(), format, count_only)?;
+		return Ok(ExitCode::SUCCESS);
+	}
+
+	let comp = input.alg.tour_computation(input.clone());
+	let pruned = comp.pruned();
+	let status = match &comp {
+		Computation::Successful { .. } => ExitCode::SUCCESS,
+		Computation::Failed { .. } => ExitCode::from(NO_TOUR),
+		Computation::GivenUp { .. } => ExitCode::from(GIVEN_UP),
+	};
+	if let Some(path) = &export {
+		let tour = match &comp {
+			Computation::Successful { solution, .. } => Some(solution),
+			_ => None,
+		};
+		export_diagram(path, &input, tour, export_scale)?;
+	}
+
+	match format {
+		Format::Json => println!("{}", serde_json::to_string(&comp)?),
+		Format::Moves | Format::Notation => match comp {
+			Computation::Successful {
+				solution,
+				explored_states,
+				..
+			} => {
+				format.print_tour(&solution);
+				eprintln!(
+					"Found a solution after {} states, with {} moves",
+					explored_states,
+					solution.len()
+				);
+			}
+			Computation::Failed {
+				impossible: Some(impossible),
+				..
+			} => {
+				eprintln!("No solution can exist: {}", impossible);
+			}
+			Computation::Failed { total_states, .. } => {
+				eprintln!("Failed to find a solution after {} states", total_states);
+			}
+			Computation::GivenUp {
+				explored_states,
+				reason,
+				elapsed,
+				..
+			} => {
+				eprintln!(
+					"Given up after {} states and {:.1}s, {}",
+					explored_states,
+					elapsed.as_secs_f32(),
+					reason
+				);
+			}
+		},
+	}
+	if !matches!(format, Format::Json) && pruned.total() > 0 {
+		eprintln!("{}", pruned);
+	}
+
+	Ok(status)
+}
+
+/// Draws the board, and the tour if one was found, as an SVG or PNG depending on the extension of `path`.
+/// DOT and GraphML files get the move graph instead
+fn export_diagram(
+	path: &Path,
+	input: &OwnedComputeInput,
+	tour: Option<&Moves>,
+	scale: f32,
+) -> anyhow::Result<()> {
+	let mut diagram = Diagram::new(
+		input.board_options.clone(),
+		input.piece.clone(),
+		Some(input.start),
+	);
+	if let Some(tour) = tour {
+		diagram.moves = tour.iter().map(|m| (*m, diagram::GREEN)).collect();
+	}
+	let no_tour = Moves::default();
+	let tour = tour.unwrap_or(&no_tour);
+	let bytes = match path.extension().and_then(|extension| extension.to_str()) {
+		Some("svg") => diagram.to_svg().into_bytes(),
+		Some("png") => render_png(&diagram.to_svg(), scale)?,
+		Some("dot") => to_dot(&input.board_options, &input.piece, tour).into_bytes(),
+		Some("graphml") => to_graphml(&input.board_options, &input.piece, tour).into_bytes(),
+		_ => bail!(
+			"--export needs a .svg, .png, .dot or .graphml file, got {}",
+			path.display()
+		),
+	};
+	std::fs::write(path, bytes).with_context(|| format!("Couldn't write {}", path.display()))
+}
+
+/// Prints every tour as it is found, then a summary.
+/// As JSON, that is one line per tour and the [cap_solver::solver::algs::Enumeration] last
+fn enumerate_all(
+	input: &OwnedComputeInput,
+	kind: TourKind,
+	format: Format,
+	count_only: bool,
+) -> anyhow::Result<()> {
+	let enumeration = enumerate_tours(input, kind, &ComputeHandle::default(), |moves| {
+		if !count_only {
+			format.print_tour(&moves);
+			if matches!(format, Format::Moves) {
+				println!();
+			}
+		}
+		ControlFlow::Continue(())
+	});
+
+	match format {
+		Format::Json => println!("{}", serde_json::to_string(&enumeration)?),
+		Format::Moves | Format::Notation => {
+			match enumeration.given_up {
+				None => eprintln!(
+					"Found all {} {} tours after {} states",
+					enumeration.found,
+					kind.to_string().to_lowercase(),
+					enumeration.explored_states
+				),
+				Some(reason) => eprintln!(
+					"Found {} {} tours before giving up after {} states, {}",
+					enumeration.found,
+					kind.to_string().to_lowercase(),
+					enumeration.explored_states,
+					reason
+				),
+			}
+			if enumeration.pruned.total() > 0 {
+				eprintln!("{}", enumeration.pruned);
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -1,6 +1,10 @@
 use crate::{
 	board::manual::UnstableSavedState,
-	solver::{algs::Algorithm, pieces::StandardPieces, BoardOptions, Moves},
+	solver::{
+		algs::{Algorithm, PruningRules},
+		pieces::Piece,
+		BoardOptions, Moves,
+	},
 	ChessPoint, ProgramState,
 };
 use bevy::prelude::*;
@@ -14,9 +18,15 @@ mod automatic;
 mod cam_zoom;
 mod coloured_moves;
 mod compute;
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
 mod hotkeys;
+mod layout_editor;
 mod manual;
+mod notation_ui;
+mod piece_editor;
 mod saftey_cap;
+mod share_link;
 mod shared;
 mod squares;
 mod ui;
@@ -54,13 +64,17 @@ pub struct SharedState {
 	/// Set using [set_alg]
 	pub alg: Algorithm,
 	pub safety_cap: SafteyCap,
+	pub pruning: PruningRules,
 
 	/// Set using [set_board_options]
 	pub board_options: BoardOptions,
 
 	/// Set using [set_start]
 	pub start: Option<ChessPoint>,
-	pub piece: StandardPieces,
+	pub piece: Piece,
+	pub piece_editor: piece_editor::PieceEditor,
+	pub layout_editor: layout_editor::LayoutEditor,
+	pub notation: notation_ui::NotationState,
 
 	// visuals
 	/// Moves that are displayed on the board.
@@ -80,6 +94,8 @@ pub struct SharedState {
 	// manual
 	pub manual_freedom: ManualFreedom,
 	pub save_state: SaveState,
+	pub share_link_error: Option<String>,
+	pub export_error: Option<String>,
 	/// Colour of next move
 	pub viz_colour: VizColour,
 
@@ -145,7 +161,9 @@ mod shared_state {
 				start: self.start?,
 				board_options: self.board_options,
 				piece: self.piece.into(),
-				safety_cap: self.safety_cap.into(),
+				safety_cap: self.safety_cap.states(),
+				time_budget: self.safety_cap.time_budget(),
+				pruning: self.pruning,
 			})
 		}
 
@@ -157,7 +175,9 @@ mod shared_state {
 				start,
 				board_options: self.board_options,
 				piece: self.piece.into(),
-				safety_cap: self.safety_cap.into(),
+				safety_cap: self.safety_cap.states(),
+				time_budget: self.safety_cap.time_budget(),
+				pruning: self.pruning,
 			}
 		}
 
@@ -216,6 +236,7 @@ use self::{
 fn setup(
 	mut commands: Commands,
 	data: Option<Res<crate::weburl::InitialLoadedID>>,
+	shared_board: Option<Res<crate::weburl::SharedBoard>>,
 	mut to_manual: ResMut<NextState<ProgramState>>,
 ) {
 	let mut state = SharedState::default();
@@ -245,6 +266,10 @@ fn setup(
 				error!("No data with that ID found");
 			}
 		}
+	} else if let Some(board) = shared_board {
+		info!("App running with a shared board");
+		to_manual.set(ProgramState::Manual);
+		state.load_shared_board(board.into_inner().clone());
 	} else {
 		info!("App running without data");
 	}
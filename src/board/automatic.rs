@@ -1,7 +1,7 @@
 use crate::{errors::Error, solver::CellOption, ProgramState};
 
 use super::{
-	compute::compute_from_state,
+	compute::{compute_from_state, ComputeManager},
 	hotkeys::Hotkeyable,
 	squares::{CellClicked, CellHovered},
 	*,
@@ -9,19 +9,29 @@ use super::{
 use bevy_egui_controls::ControlPanel;
 use strum::{EnumIs, EnumIter};
 
+pub use solutions::SolutionBrowser;
+mod solutions;
 mod summary;
 
 pub struct AutomaticPlugin;
 impl Plugin for AutomaticPlugin {
 	fn build(&self, app: &mut App) {
-		app.add_systems(
-			(
-				compute_from_state,
-				handle_cell_clicked,
-				highlight_hovered_cell,
+		app
+			.init_resource::<ComputeManager>()
+			.init_resource::<SolutionBrowser>()
+			.add_systems(
+				(
+					compute_from_state,
+					SolutionBrowser::sys_show_selected.after(compute_from_state),
+					handle_cell_clicked,
+					highlight_hovered_cell,
+				)
+					.in_set(OnUpdate(ProgramState::Automatic)),
 			)
-				.in_set(OnUpdate(ProgramState::Automatic)),
-		);
+			.add_systems(
+				(ComputeManager::sys_cancel_all, SolutionBrowser::sys_clear)
+					.in_schedule(OnExit(ProgramState::Automatic)),
+			);
 	}
 }
 
@@ -0,0 +1,163 @@
+//! Finding every solution for the current start and stepping through them on the board
+
+use std::{
+	ops::ControlFlow,
+	sync::{Arc, Mutex},
+};
+
+use bevy_egui::egui::{self, Ui};
+use strum::IntoEnumIterator;
+
+use crate::{
+	board::compute::start_executing_task,
+	solver::algs::{enumerate_tours, ComputeHandle, Enumeration, OwnedComputeInput, TourKind},
+};
+
+use super::*;
+
+/// Solutions past this are still counted, but not kept around to be shown
+const MAX_SOLUTIONS: usize = 10_000;
+
+/// Every tour found from one start, so the user can step through solution N of M.
+/// Forgotten as soon as the start or board changes
+#[derive(Resource, Default)]
+pub struct SolutionBrowser {
+	kind: TourKind,
+	enumerating: Option<Enumerating>,
+	/// Index into the found solutions, shown on the board instead of the normal solution
+	selected: Option<usize>,
+}
+
+struct Enumerating {
+	input: OwnedComputeInput,
+	handle: ComputeHandle,
+	solutions: Arc<Mutex<Vec<Moves>>>,
+	/// Set once the search has finished
+	summary: Arc<Mutex<Option<Enumeration>>>,
+}
+
+impl SolutionBrowser {
+	fn start(&mut self, input: OwnedComputeInput) {
+		self.clear();
+
+		let enumerating = Enumerating {
+			input: input.clone(),
+			handle: ComputeHandle::default(),
+			solutions: Arc::new(Mutex::new(Vec::new())),
+			summary: Arc::new(Mutex::new(None)),
+		};
+		let kind = self.kind;
+		let handle = enumerating.handle.clone();
+		let solutions = enumerating.solutions.clone();
+		let summary = enumerating.summary.clone();
+		start_executing_task(move || {
+			let enumeration = enumerate_tours(&input, kind, &handle, |moves| {
+				let mut solutions = solutions.lock().unwrap();
+				if solutions.len() < MAX_SOLUTIONS {
+					solutions.push(moves);
+				}
+				ControlFlow::Continue(())
+			});
+			*summary.lock().unwrap() = Some(enumeration);
+		});
+
+		self.enumerating = Some(enumerating);
+	}
+
+	fn clear(&mut self) {
+		if let Some(enumerating) = self.enumerating.take() {
+			enumerating.handle.cancel();
+		}
+		self.selected = None;
+	}
+
+	/// Runs after [compute_from_state] so the selected solution wins
+	pub fn sys_show_selected(state: ResMut<SharedState>, mut browser: ResMut<SolutionBrowser>) {
+		let Some(enumerating) = &browser.enumerating else {
+			return;
+		};
+		if state.clone().get_compute_state().as_ref() != Some(&enumerating.input) {
+			browser.clear();
+			return;
+		}
+
+		if let Some(selected) = browser.selected {
+			if let Some(moves) = enumerating.solutions.lock().unwrap().get(selected) {
+				state.into_inner().set_moves(moves.clone());
+			}
+		}
+	}
+
+	pub fn sys_clear(mut browser: ResMut<SolutionBrowser>) {
+		browser.clear();
+	}
+
+	pub fn ui(&mut self, ui: &mut Ui, input: Option<OwnedComputeInput>) {
+		let Some(input) = input else {
+			ui.label("Hover over a cell to choose where to start");
+			return;
+		};
+
+		ui.horizontal(|ui| {
+			for kind in TourKind::iter() {
+				if ui
+					.selectable_value(&mut self.kind, kind, kind.to_string())
+					.changed()
+				{
+					self.clear();
+				}
+			}
+		});
+		if ui.button("Find all").clicked() {
+			self.start(input);
+		}
+
+		let Some(enumerating) = &self.enumerating else {
+			return;
+		};
+		let found = enumerating.solutions.lock().unwrap().len();
+		let summary = *enumerating.summary.lock().unwrap();
+		match summary {
+			None => {
+				ui.label(format!(
+					"Searching, found {} after {} states",
+					found,
+					enumerating.handle.explored_states()
+				));
+				if ui.button("Cancel").clicked() {
+					enumerating.handle.cancel();
+				}
+			}
+			Some(enumeration) => match enumeration.given_up {
+				None => {
+					ui.label(format!("Found all {} solutions", enumeration.found));
+				}
+				Some(reason) => {
+					ui.colored_label(
+						egui::Color32::YELLOW,
+						format!("Found {} solutions, then {}", enumeration.found, reason),
+					);
+				}
+			},
+		}
+		if found == MAX_SOLUTIONS {
+			ui.label(format!("Only the first {} can be shown", MAX_SOLUTIONS));
+		}
+		if found == 0 {
+			return;
+		}
+
+		ui.horizontal(|ui| {
+			let mut n = self.selected.map(|i| i + 1).unwrap_or(0);
+			if ui.button("Previous").clicked() {
+				n = n.saturating_sub(1).max(1);
+			}
+			ui.add(egui::DragValue::new(&mut n).clamp_range(1..=found));
+			ui.label(format!("of {}", found));
+			if ui.button("Next").clicked() {
+				n = (n + 1).min(found);
+			}
+			self.selected = Some(n.max(1) - 1);
+		});
+	}
+}
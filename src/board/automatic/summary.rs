@@ -4,18 +4,41 @@ use bevy_egui::egui::{Ui, Color32};
 
 use crate::solver::algs::{Computation, try_get_cached_solution};
 
-use super::SharedState;
+use super::{ComputeManager, SharedState};
 
 impl SharedState {
-	pub fn summarize(&self, ui: &mut Ui) -> Option<String> {
-		let comp = try_get_cached_solution(&self.clone().get_compute_state()?)?;
-		Some(match comp {
-			Computation::Failed { total_states } => {
+	pub fn summarize(&self, ui: &mut Ui, manager: &mut ComputeManager) -> Option<String> {
+		let input = self.clone().get_compute_state()?;
+		if let Some(explored_states) = manager.progress(&input) {
+			let msg = format!("Computing, explored {} states so far", explored_states);
+			ui.label(msg.clone());
+			if ui.button("Cancel").clicked() {
+				manager.cancel(&input);
+			}
+			return Some(msg);
+		}
+
+		let comp = try_get_cached_solution(&input)?;
+		let pruned = comp.pruned();
+		let msg = match comp {
+			Computation::Failed {
+				impossible: Some(impossible),
+				..
+			} => {
+				let msg = format!("No solution can exist: {}", impossible);
+				ui.colored_label(Color32::RED, msg.clone());
+				msg
+			}
+			Computation::Failed { total_states, .. } => {
 				let msg = format!("Failed to find a solution after {} states", total_states);
 				ui.colored_label(Color32::RED, msg.clone());
 				msg
 			}
-			Computation::Successful { solution, explored_states } => {
+			Computation::Successful {
+				solution,
+				explored_states,
+				..
+			} => {
 				let msg = format!(
 					"Found a solution after {} states, with {} moves",
 					explored_states,
@@ -24,11 +47,25 @@ impl SharedState {
 				ui.colored_label(Color32::GREEN, msg.clone());
 				msg
 			}
-			Computation::GivenUp { explored_states } => {
-				let msg = format!("Given up after {} states", explored_states);
+			Computation::GivenUp {
+				explored_states,
+				reason,
+				elapsed,
+				..
+			} => {
+				let msg = format!(
+					"Given up after {} states and {:.1}s, {}",
+					explored_states,
+					elapsed.as_secs_f32(),
+					reason
+				);
 				ui.colored_label(Color32::YELLOW, msg.clone());
 				msg
 			}
-		})
+		};
+		if pruned.total() > 0 {
+			ui.label(pruned.to_string());
+		}
+		Some(msg)
 	}
 }
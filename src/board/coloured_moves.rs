@@ -2,7 +2,14 @@ use bevy::reflect::{Reflect, FromReflect};
 use derive_more::{Deref, DerefMut, From, Into};
 use serde::{Serialize, Deserialize};
 
-use crate::{solver::{Move, Moves}, ChessPoint};
+use crate::{
+	solver::{
+		notation::{read_tour, NotationError},
+		pieces::ChessPiece,
+		BoardOptions, Move, Moves,
+	},
+	ChessPoint,
+};
 
 use super::squares::visualization::VizColour;
 
@@ -59,4 +66,24 @@ impl ColouredMoves {
 		}
 		self
 	}
-}
\ No newline at end of file
+
+	/// Reads a tour in algebraic notation, see [crate::solver::notation].
+	/// Like moves made by hand, each unconnected piece starts with a move to itself
+	pub fn from_notation(
+		text: &str,
+		piece: &ChessPiece,
+		options: &BoardOptions,
+		col: VizColour,
+	) -> Result<Self, NotationError> {
+		let mut coloured = Self::default();
+		let mut current = None;
+		for m in read_tour(text, piece, options)?.iter() {
+			if current != Some(m.from) && m.from != m.to {
+				coloured.push((Move::new(m.from, m.from), col));
+			}
+			coloured.push((*m, col));
+			current = Some(m.to);
+		}
+		Ok(coloured)
+	}
+}
@@ -1,9 +1,73 @@
+use std::collections::HashMap;
+
 use super::{squares::CellMark, *};
-use crate::solver::algs::{self, Computation, OwnedComputeInput};
+use crate::solver::algs::{self, ComputeHandle, Computation, OwnedComputeInput};
+
+/// Keeps track of computations running in the background.
+/// There is at most one task per [OwnedComputeInput], finished results end up in the algs cache
+#[derive(Resource, Default)]
+pub struct ComputeManager {
+	running: HashMap<OwnedComputeInput, ComputeHandle>,
+}
+
+impl ComputeManager {
+	/// Starts computing `input` in the background, unless it is already running.
+	/// Finished tasks are kept until cancelled, so a result that didn't make it into the cache
+	/// isn't recomputed every frame
+	pub fn request(&mut self, input: OwnedComputeInput) {
+		if self.running.contains_key(&input) {
+			return;
+		}
+
+		let handle = ComputeHandle::default();
+		self.running.insert(input.clone(), handle.clone());
+		start_executing_task(move || {
+			algs::Algorithm::tour_computation_cached(input, &handle);
+			handle.finish();
+		});
+	}
+
+	/// Number of states explored so far, if `input` is still being computed
+	pub fn progress(&self, input: &OwnedComputeInput) -> Option<u128> {
+		self
+			.running
+			.get(input)
+			.filter(|handle| !handle.is_finished())
+			.map(|handle| handle.explored_states())
+	}
+
+	/// Cancels the task computing `input`.
+	/// It stays tracked, so it isn't restarted until the user hovers away and back
+	pub fn cancel(&mut self, input: &OwnedComputeInput) {
+		if let Some(handle) = self.running.get(input) {
+			handle.cancel();
+		}
+	}
+
+	/// Cancels every task except the one computing `keep`
+	pub fn cancel_others(&mut self, keep: Option<&OwnedComputeInput>) {
+		self.running.retain(|input, handle| {
+			if Some(input) == keep {
+				true
+			} else {
+				handle.cancel();
+				false
+			}
+		});
+	}
+
+	pub fn sys_cancel_all(mut manager: ResMut<ComputeManager>) {
+		manager.cancel_others(None);
+	}
+}
 
 /// Syncs [SharedState] resource with computations
-pub fn compute_from_state(state: ResMut<SharedState>) {
-	if let Some(compute_state) = state.clone().get_compute_state() {
+pub fn compute_from_state(state: ResMut<SharedState>, mut manager: ResMut<ComputeManager>) {
+	let compute_state = state.clone().get_compute_state();
+	// the user hovered away from anything else
+	manager.cancel_others(compute_state.as_ref());
+
+	if let Some(compute_state) = compute_state {
 		// try get from algs cache
 		if let Some(comp) = algs::try_get_cached_solution(&compute_state) {
 			match comp {
@@ -16,10 +80,7 @@ pub fn compute_from_state(state: ResMut<SharedState>) {
 			}
 		} else {
 			// not cached
-			let comp_state = compute_state.clone();
-			start_executing_task(compute_state.clone(), || {
-				algs::Algorithm::tour_computation_cached(comp_state)
-			});
+			manager.request(compute_state);
 		}
 	}
 }
@@ -59,29 +120,18 @@ impl SharedState {
 	}
 }
 
-// static COMPUTATIONS_TO_HANDLE: Lazy<Mutex<HashMap<ComputeInput, Computation>>> =
-// 	Lazy::new(|| Mutex::new(HashMap::new()));
-
-fn start_executing_task(
-	_state: OwnedComputeInput,
-	task: impl FnOnce() -> Option<Computation> + Send + 'static,
-) {
+pub(super) fn start_executing_task(task: impl FnOnce() + Send + 'static) {
 	#[cfg(not(target_arch = "wasm32"))]
 	{
 		use std::thread;
 
 		// create a new thread to run the task on
-		thread::spawn(move || {
-			let _res = task();
-
-			// COMPUTATIONS_TO_HANDLE.lock().unwrap().insert(state, res);
-		});
+		thread::spawn(task);
 	}
 
 	#[cfg(target_arch = "wasm32")]
 	{
-		let res = task();
-		// COMPUTATIONS_TO_HANDLE.lock().unwrap().insert(state, res);
+		task();
 	}
 	// TODO: Mess around with WebWorkers & don't break audio?
 	// futures::executor::block_on(async move {
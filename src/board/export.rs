@@ -0,0 +1,84 @@
+//! Saving the board and its moves as an image, see [crate::diagram],
+//! or as the graph of moves, see [crate::solver::move_graph]
+
+use std::path::Path;
+
+use anyhow::Context;
+use bevy_egui::egui::{Color32, Ui};
+
+use crate::{
+	diagram::{render_png, Diagram},
+	solver::move_graph::{to_dot, to_graphml},
+};
+
+use super::*;
+
+impl SharedState {
+	/// Drawn like the 3D board, with the same cell colours and move colours
+	fn diagram(&self) -> Diagram {
+		let mut diagram = Diagram::new(self.board_options.clone(), (&self.piece).into(), self.start);
+		diagram.cell_colours = self
+			.cell_colours()
+			.into_iter()
+			.map(|(point, colour)| (point, colour.as_rgba_f32()))
+			.collect();
+		if self.visual_opts.show_visualisation {
+			diagram.moves = self
+				.moves
+				.iter()
+				.flat_map(|moves| moves.iter())
+				.map(|(m, colour)| (*m, Color::from(*colour).as_rgba_f32()))
+				.collect();
+		}
+		diagram.show_numbers = self.visual_opts.show_numbers;
+		diagram
+	}
+
+	/// Depending on `extension`, the board drawn or the move graph with the current moves highlighted
+	fn export(&self, path: &Path, extension: &str) -> Result<(), anyhow::Error> {
+		let piece = (&self.piece).into();
+		let tour: Moves = self
+			.moves
+			.iter()
+			.flat_map(|moves| moves.iter())
+			.map(|(m, _)| *m)
+			.collect();
+		let bytes = match extension {
+			"png" => render_png(&self.diagram().to_svg(), 2.)?,
+			"dot" => to_dot(&self.board_options, &piece, &tour).into_bytes(),
+			"graphml" => to_graphml(&self.board_options, &piece, &tour).into_bytes(),
+			_ => self.diagram().to_svg().into_bytes(),
+		};
+		std::fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+	}
+
+	pub fn export_ui(&mut self, ui: &mut Ui) {
+		ui.horizontal(|ui| {
+			for (label, filter, extension) in [
+				("Export SVG...", "Image", "svg"),
+				("Export PNG...", "Image", "png"),
+				("Export DOT...", "Graph", "dot"),
+				("Export GraphML...", "Graph", "graphml"),
+			] {
+				if !ui.button(label).clicked() {
+					continue;
+				}
+				let Some(path) = rfd::FileDialog::new()
+					.add_filter(filter, &[extension])
+					.set_file_name(&format!("board.{}", extension))
+					.save_file()
+				else {
+					continue;
+				};
+				self.export_error = self
+					.export(&path, extension)
+					.err()
+					.map(|err| format!("{:#}", err));
+			}
+		});
+		ui.label("Images of the board and moves shown, or the graph of moves for graph tools");
+		if let Some(err) = &self.export_error {
+			ui.colored_label(Color32::RED, err);
+		}
+	}
+}
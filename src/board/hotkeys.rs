@@ -19,10 +19,55 @@ pub trait Hotkeyable: Into<KeyCode> + Clone + IntoEnumIterator {
 	}
 }
 
+impl Algorithm {
+	/// Registered solvers aren't an enum, so can't be [Hotkeyable]
+	fn activate_hotkeys(&mut self, keys: &Res<Input<KeyCode>>) {
+		for alg in Algorithm::all() {
+			if let Some(key) = alg.hotkey().and_then(key_code_from_char) {
+				if keys.just_pressed(key) {
+					*self = alg;
+				}
+			}
+		}
+	}
+}
+
+fn key_code_from_char(c: char) -> Option<KeyCode> {
+	Some(match c.to_ascii_lowercase() {
+		'a' => KeyCode::A,
+		'b' => KeyCode::B,
+		'c' => KeyCode::C,
+		'd' => KeyCode::D,
+		'e' => KeyCode::E,
+		'f' => KeyCode::F,
+		'g' => KeyCode::G,
+		'h' => KeyCode::H,
+		'i' => KeyCode::I,
+		'j' => KeyCode::J,
+		'k' => KeyCode::K,
+		'l' => KeyCode::L,
+		'm' => KeyCode::M,
+		'n' => KeyCode::N,
+		'o' => KeyCode::O,
+		'p' => KeyCode::P,
+		'q' => KeyCode::Q,
+		'r' => KeyCode::R,
+		's' => KeyCode::S,
+		't' => KeyCode::T,
+		'u' => KeyCode::U,
+		'v' => KeyCode::V,
+		'w' => KeyCode::W,
+		'x' => KeyCode::X,
+		'y' => KeyCode::Y,
+		'z' => KeyCode::Z,
+		_ => return None,
+	})
+}
+
 pub fn hotkeys(state: ResMut<SharedState>, keys: Res<Input<KeyCode>>) {
 	let state = state.into_inner();
 
-	if state.save_state.is_typing {
+	if state.save_state.is_typing || state.piece_editor.is_typing || state.layout_editor.is_typing {
 		return;
 	}
 
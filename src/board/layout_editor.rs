@@ -0,0 +1,68 @@
+//! Pasting in boards drawn as text, see [crate::solver::layout]
+
+use bevy_egui::egui::{Color32, TextEdit, Ui, Widget};
+
+use crate::solver::{
+	layout::{read_layout_up_to, write_layout},
+	pieces::Piece,
+};
+
+use super::*;
+
+#[derive(Default, Clone, Reflect, FromReflect)]
+pub struct LayoutEditor {
+	pub text: String,
+	pub error_str: Option<String>,
+	pub is_typing: bool,
+}
+
+impl SharedState {
+	fn load_layout(&mut self) -> StateInvalidated {
+		// as large as the selected algorithm handles
+		let max_size = self.alg.max_board_size() as u16;
+		let layout = match read_layout_up_to(&self.layout_editor.text, max_size) {
+			Ok(layout) => layout,
+			Err(err) => {
+				self.layout_editor.error_str = Some(err.to_string());
+				return StateInvalidated::Valid;
+			}
+		};
+
+		if let Some(piece) = layout.piece {
+			if let Piece::Custom(custom) = &piece {
+				self.piece_editor.remember(custom.clone());
+			}
+			self.piece = piece;
+		}
+		self.board_options = layout.board_options;
+		self.layout_editor.error_str = None;
+		StateInvalidated::InvalidatedAndClearStart
+	}
+
+	pub fn layout_ui(&mut self, ui: &mut Ui) {
+		ui.label("Rows of . available, # unavailable, x eliminated and T target cells");
+
+		self.layout_editor.is_typing = false;
+		if TextEdit::multiline(&mut self.layout_editor.text)
+			.code_editor()
+			.hint_text("piece: knight\n..#\n.T.")
+			.ui(ui)
+			.has_focus()
+		{
+			self.layout_editor.is_typing = true;
+		}
+
+		ui.horizontal(|ui| {
+			if ui.button("Use this layout").clicked() {
+				self.load_layout().invalidates(self);
+			}
+			if ui.button("From current board").clicked() {
+				self.layout_editor.text = write_layout(&self.board_options, Some(&self.piece));
+				self.layout_editor.error_str = None;
+			}
+		});
+		if let Some(err) = &self.layout_editor.error_str {
+			ui.colored_label(Color32::RED, err);
+		}
+	}
+}
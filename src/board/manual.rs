@@ -12,6 +12,8 @@ pub struct ManualState;
 impl Plugin for ManualState {
 	fn build(&self, app: &mut App) {
 		app.add_systems((highlight_hovered_cell, handle_cell_clicked).in_set(OnUpdate(ProgramState::Manual)));
+		#[cfg(not(target_arch = "wasm32"))]
+		app.add_system(SharedState::sys_load_dropped_files);
 	}
 }
 
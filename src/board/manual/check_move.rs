@@ -74,7 +74,11 @@ impl SharedState {
 				let moves: Moves = moves.moves();
 				if moves.is_empty() {
 					MoveWarning::NoMoves
-				} else if !ChessPiece::from(self.piece).is_valid_move(moves.last().unwrap().to, next) {
+				} else if !ChessPiece::from(&self.piece).is_valid_move(
+					moves.last().unwrap().to,
+					next,
+					&self.board_options,
+				) {
 					MoveWarning::NotValid
 				} else if moves.get_all_passed_through_points().contains(&next) {
 					MoveWarning::AlreadyDone
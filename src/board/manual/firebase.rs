@@ -1,5 +1,5 @@
 use bevy::{utils::HashMap, reflect::{Reflect, FromReflect}};
-use derive_more::Deref;
+use derive_more::{Deref, From};
 #[cfg(not(target_arch = "wasm32"))]
 use firebase_rs::Firebase;
 use once_cell::sync::Lazy;
@@ -8,6 +8,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::info;
 
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::store::SaveStore;
 use super::{UnstableSavedState, MetaData};
 
 const BASE_URL: &str =
@@ -21,11 +26,13 @@ static DB: Lazy<Firebase> = Lazy::new(|| {
 		.at(&crate::meta::VERSION_APPEND)
 });
 
-#[derive(Debug, Hash, Clone, PartialEq, Eq, Deref, Serialize, Deserialize, Reflect, FromReflect)]
+#[derive(
+	Debug, Hash, Clone, PartialEq, Eq, Deref, From, Serialize, Deserialize, Reflect, FromReflect,
+)]
 pub struct ID(String);
 
 impl ID {
-	fn new() -> Self {
+	pub(super) fn new() -> Self {
 		// generate a random 8 character string
 		let mut rng = rand::thread_rng();
 		let id: Vec<u8> = std::iter::repeat(())
@@ -105,4 +112,64 @@ pub async fn get_metadata_list() -> Option<Vec<MetaData>> {
 	info!("finished getting all metadata");
 
 	Some(metadatas)
-}
\ No newline at end of file
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main(flavor = "current_thread")]
+async fn update_in_db(id: ID, state: UnstableSavedState) -> Option<()> {
+	info!("updating db at {:?}", id.clone());
+
+	let metadata = state.metadata.clone();
+	let payload = Payload::new(id.clone(), state);
+
+	DB.update(&payload).await.ok()?;
+	DB.at("metadata").update(&json!({ id.0.clone(): metadata })).await.ok()?;
+
+	info!("finished updating db");
+	Some(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main(flavor = "current_thread")]
+async fn delete_from_db(id: ID) -> Option<()> {
+	info!("deleting from db at {:?}", id.clone());
+
+	DB.at(&id.0).delete().await.ok()?;
+	DB.at("metadata").at(&id.0).delete().await.ok()?;
+
+	info!("finished deleting from db");
+	Some(())
+}
+
+/// The shared database, also read by the web version
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FirebaseStore;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveStore for FirebaseStore {
+	fn save(&self, state: UnstableSavedState) -> Result<ID, anyhow::Error> {
+		save_to_db(state).context("Failed to save to the database")
+	}
+
+	fn load(&self, id: &ID) -> Result<UnstableSavedState, anyhow::Error> {
+		get_from_db(id.clone()).context("Failed to load save from the database")
+	}
+
+	fn list_metadata(&self) -> Result<Vec<MetaData>, anyhow::Error> {
+		get_metadata_list().context("Failed to get list of saves from the database")
+	}
+
+	fn delete(&self, id: &ID) -> Result<(), anyhow::Error> {
+		delete_from_db(id.clone()).context("Failed to delete save from the database")
+	}
+
+	fn update(&self, id: &ID, state: UnstableSavedState) -> Result<(), anyhow::Error> {
+		// updating a missing save would create it
+		self.load(id)?;
+		update_in_db(id.clone(), state).context("Failed to update save in the database")
+	}
+
+	fn web_url(&self, id: &ID) -> Option<String> {
+		Some(crate::weburl::create_url_with_id(id.inner().into()))
+	}
+}
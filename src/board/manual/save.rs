@@ -1,33 +1,91 @@
 use anyhow::Context;
 use bevy::reflect::{Reflect, FromReflect};
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 use tracing::info;
 
 use crate::{
-	board::{coloured_moves::ColouredMoves, SharedState, VizColour},
-	solver::{BoardOptions, Move},
+	board::{coloured_moves::ColouredMoves, SafteyCap, SharedState, VizColour},
+	solver::{
+		algs::{Algorithm, PruningRules},
+		pieces::{CustomPiece, Piece},
+		BoardOptions, ChessPoint, Move,
+	},
 };
 
 pub use ui::SaveState;
 mod ui;
+#[cfg(not(target_arch = "wasm32"))]
+mod file;
+#[cfg(not(target_arch = "wasm32"))]
+mod store;
 
 #[path = "firebase.rs"]
 mod firebase;
 
 /// Serialized
-pub type StableSavedState = v0_3_x::StableSavedState;
+pub type StableSavedState = v0_4_x::StableSavedState;
+
+/// Every save format there has been, oldest first.
+/// Saves since v0.4 are tagged with theirs, older ones are told apart by their fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, strum::Display)]
+pub enum FormatVersion {
+	#[serde(rename = "0.2")]
+	#[strum(serialize = "v0.2")]
+	V0_2,
+	#[serde(rename = "0.3")]
+	#[strum(serialize = "v0.3")]
+	V0_3,
+	#[serde(rename = "0.4")]
+	#[strum(serialize = "v0.4")]
+	V0_4,
+}
+
+impl FormatVersion {
+	/// Written by [UnstableSavedState::into_json]
+	pub const CURRENT: Self = Self::V0_4;
+
+	fn of(json: &serde_json::Value) -> Result<Self, anyhow::Error> {
+		if let Some(version) = json.get("version") {
+			return serde_json::from_value(version.clone())
+				.with_context(|| format!("Unknown save format version {}", version));
+		}
+		if json.get("colours").is_some() {
+			Ok(Self::V0_2)
+		} else {
+			Ok(Self::V0_3)
+		}
+	}
+
+	/// An example save, loaded by the tests so old saves keep loading.
+	/// A new version doesn't compile until it has one
+	#[cfg(test)]
+	fn fixture(self) -> &'static str {
+		match self {
+			Self::V0_2 => include_str!("save/fixtures/v0_2.json"),
+			Self::V0_3 => include_str!("save/fixtures/v0_3.json"),
+			Self::V0_4 => include_str!("save/fixtures/v0_4.json"),
+		}
+	}
+}
 
 /// Convertable from/into (Unstable)[SharedState], convertable from/into [StableSavedState].
 /// Bridge from stability into unstability
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnstableSavedState {
 	metadata: MetaData,
 	moves: ColouredMoves,
+	/// Including the recommended moves
 	board_options: BoardOptions,
+	piece: Piece,
+	alg: Algorithm,
+	safety_cap: SafteyCap,
+	pruning: PruningRules,
+	start: Option<ChessPoint>,
 }
 
 /// This is stable
-#[derive(Serialize, Deserialize, Debug, Clone, Reflect, FromReflect)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reflect, FromReflect)]
 pub struct MetaData {
 	pub id: Option<firebase::ID>,
 	pub title: String,
@@ -58,6 +116,11 @@ impl TryFrom<SharedState> for UnstableSavedState {
 			metadata: state.clone().try_into()?,
 			moves: state.moves.ok_or("No moves to save")?,
 			board_options: state.board_options,
+			piece: state.piece,
+			alg: state.alg,
+			safety_cap: state.safety_cap,
+			pruning: state.pruning,
+			start: state.start,
 		})
 	}
 }
@@ -68,6 +131,11 @@ impl SharedState {
 		UnstableSavedState {
 			board_options: self.board_options.clone(),
 			moves: self.moves.clone().unwrap(),
+			piece: self.piece.clone(),
+			alg: self.alg,
+			safety_cap: self.safety_cap.clone(),
+			pruning: self.pruning,
+			start: self.start,
 			metadata: self.try_into().unwrap_or_else(|err| {
 				info!("De-Serializing dangerously gave error: {:?}", err);
 				let mut metadata = MetaData::dangerous_default();
@@ -81,28 +149,53 @@ impl SharedState {
 impl UnstableSavedState {
 	/// Serialize into the Stable state
 	pub fn into_json(self) -> String {
-		let data = v0_3_x::StableSavedState::from(self);
+		let data = StableSavedState::from(self);
 		serde_json::to_string(&data).expect("Cannot serialise data")
 	}
 
-	/// Hnadles depreciated format
+	/// Like [Self::into_json], but indented so saves kept in git diff nicely
+	pub fn into_pretty_json(self) -> String {
+		let data = StableSavedState::from(self);
+		serde_json::to_string_pretty(&data).expect("Cannot serialise data")
+	}
+
+	/// Loads any [FormatVersion], migrating it one version at a time up to the current one
 	pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
-		match serde_json::from_str::<v0_3_x::StableSavedState>(json) {
-			Ok(state) => Ok(state.into()),
-			Err(new_err) => {
-				let old_err = v0_2_x::try_depreciated_from_json(json);
-				if let Ok(state) = old_err {
-					return Ok(state);
-				}
-				Err(new_err).context("JSON decoding failed & depreciated failed")
+		let json: serde_json::Value = serde_json::from_str(json).context("Save is not JSON")?;
+		let version = FormatVersion::of(&json)?;
+		let failed = || format!("Failed to parse a {} save", version);
+
+		let state = match version {
+			FormatVersion::V0_2 => {
+				let old = serde_json::from_value(json).with_context(failed)?;
+				let old = v0_3_x::migrate_v0_2_to_v0_3(old).with_context(failed)?;
+				v0_4_x::migrate_v0_3_to_v0_4(old)
 			}
-		}
+			FormatVersion::V0_3 => {
+				v0_4_x::migrate_v0_3_to_v0_4(serde_json::from_value(json).with_context(failed)?)
+			}
+			FormatVersion::V0_4 => serde_json::from_value(json).with_context(failed)?,
+		};
+		Ok(state.into())
 	}
 
-	pub fn apply_to_state(self, state: &mut SharedState) {
+	/// Everything but the metadata, which is returned
+	pub fn load_into(self, state: &mut SharedState) -> MetaData {
 		state.board_options = self.board_options;
 		state.moves = Some(self.moves);
-		state.web_vis = Some(self.metadata);
+		if let Piece::Custom(custom) = &self.piece {
+			state.piece_editor.remember(custom.clone());
+		}
+		state.piece = self.piece;
+		state.alg = self.alg;
+		state.safety_cap = self.safety_cap;
+		state.pruning = self.pruning;
+		state.start = self.start;
+		self.metadata
+	}
+
+	pub fn apply_to_state(self, state: &mut SharedState) {
+		state.web_vis = Some(self.load_into(state));
 		state.is_web_vis_first_render = true;
 	}
 }
@@ -125,24 +218,233 @@ fn check_serialize_deserialize_works() {
 		},
 		moves,
 		board_options,
+		piece: Default::default(),
+		alg: Default::default(),
+		safety_cap: Default::default(),
+		pruning: Default::default(),
+		start: None,
 	};
 
 	let json = data.into_json();
 	let _data = UnstableSavedState::from_json(&json).unwrap();
 }
 
+#[test]
+fn saves_and_loads_every_field() {
+	use crate::solver::{pieces::StandardPieces, Topology};
+
+	let mut moves = ColouredMoves::default();
+	moves.manual_add_move(ChessPoint::new(3, 2), VizColour::Blue);
+	moves.manual_add_move(ChessPoint::new(1, 1), VizColour::Red);
+
+	let mut board_options = BoardOptions::new(2, 3);
+	board_options.rm((1, 2));
+	board_options.add_recommended_move(Move::new(ChessPoint::new(1, 1), ChessPoint::new(3, 2)));
+	board_options.set_topology(Topology::Torus);
+
+	let saved = UnstableSavedState {
+		metadata: MetaData {
+			id: None,
+			title: "test".into(),
+			author: "test".into(),
+			description: "test".into(),
+			dimensions: (2, 3),
+		},
+		moves,
+		board_options,
+		piece: StandardPieces::ABKnight(2, 3).into(),
+		alg: Algorithm::all()[2],
+		safety_cap: SafteyCap::from_limits(None, Some(42)),
+		pruning: PruningRules {
+			dead_ends: false,
+			..Default::default()
+		},
+		start: Some(ChessPoint::new(3, 2)),
+	};
+	let round_trip =
+		|saved: UnstableSavedState| UnstableSavedState::from_json(&saved.into_json()).unwrap();
+
+	let loaded = round_trip(saved.clone());
+	assert_eq!(loaded.metadata.title, saved.metadata.title);
+	assert_eq!(loaded.moves, saved.moves);
+	assert_eq!(loaded.board_options, saved.board_options);
+	assert_eq!(loaded.piece, saved.piece);
+	assert_eq!(loaded.alg, saved.alg);
+	assert_eq!(loaded.safety_cap, saved.safety_cap);
+	assert_eq!(loaded.pruning, saved.pruning);
+	assert_eq!(loaded.start, saved.start);
+
+	let custom = Piece::Custom(CustomPiece {
+		name: "Camel".into(),
+		piece: StandardPieces::Camel.into(),
+	});
+	let pieces = StandardPieces::FAIRY.into_iter().map(Piece::from);
+	for piece in pieces.chain([StandardPieces::StandardKnight.into(), custom]) {
+		let loaded = round_trip(UnstableSavedState {
+			piece: piece.clone(),
+			..saved.clone()
+		});
+		assert_eq!(loaded.piece, piece);
+	}
+	for alg in Algorithm::all() {
+		assert_eq!(
+			round_trip(UnstableSavedState {
+				alg,
+				..saved.clone()
+			})
+			.alg,
+			alg
+		);
+	}
+}
+
+#[test]
+fn random_boards_survive_a_round_trip() {
+	use crate::solver::{CellOption, Topology};
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+	use strum::IntoEnumIterator;
+
+	let topologies: Vec<Topology> = Topology::iter().collect();
+	let colours: Vec<VizColour> = VizColour::iter().collect();
+	let mut rng = StdRng::seed_from_u64(3);
+	for _ in 0..200 {
+		let (width, height) = (rng.gen_range(2..=12), rng.gen_range(2..=12));
+		let random_point =
+			|rng: &mut StdRng| ChessPoint::new(rng.gen_range(1..=height), rng.gen_range(1..=width));
+
+		let mut board_options = BoardOptions::new(width, height);
+		for point in board_options.get_all_points() {
+			let cell = match rng.gen_range(0..4) {
+				0 => CellOption::Unavailable,
+				1 => CellOption::Eliminated,
+				n => CellOption::Available {
+					can_finish_on: n == 2,
+				},
+			};
+			board_options.set_point(point, cell);
+		}
+		for _ in 0..rng.gen_range(0..6) {
+			let recommended = Move::new(random_point(&mut rng), random_point(&mut rng));
+			board_options.add_recommended_move(recommended);
+		}
+		board_options.set_topology(topologies[rng.gen_range(0..topologies.len())]);
+
+		let mut moves = ColouredMoves::default();
+		for _ in 0..rng.gen_range(1..20) {
+			let colour = colours[rng.gen_range(0..colours.len())];
+			moves.manual_add_move(random_point(&mut rng), colour);
+		}
+
+		let saved = UnstableSavedState {
+			metadata: MetaData {
+				id: None,
+				title: "random".into(),
+				author: "test".into(),
+				description: "test".into(),
+				dimensions: board_options.dimensions(),
+			},
+			moves,
+			board_options,
+			piece: Default::default(),
+			alg: Default::default(),
+			safety_cap: Default::default(),
+			pruning: Default::default(),
+			start: rng.gen_bool(0.5).then(|| random_point(&mut rng)),
+		};
+		let json = saved.clone().into_json();
+		assert_eq!(
+			UnstableSavedState::from_json(&json).unwrap(),
+			saved,
+			"{}",
+			json
+		);
+	}
+}
+
+#[test]
+fn migrates_v0_3_saves() {
+	let json = r#"{"moves":[[[1,1],[3,2],[0.0,0.0,1.0,1.0]]],"board_options":{"[1,1]":1,"[1,2]":1,"[2,1]":1,"[2,2]":0,"[3,1]":1,"[3,2]":2},"metadata":{"id":null,"title":"v0.3","author":"test","description":"test","dimensions":[2,3]}}"#;
+	let loaded = UnstableSavedState::from_json(json).unwrap();
+	assert_eq!(loaded.metadata.title, "v0.3");
+	assert_eq!(loaded.board_options.dimensions(), (2, 3));
+	assert!(!loaded.board_options.is_available(&ChessPoint::new(2, 2)));
+	assert_eq!(loaded.moves.len(), 1);
+	// v0.3 saves were always made with the defaults
+	assert_eq!(loaded.piece, Piece::default());
+	assert_eq!(loaded.alg, Algorithm::default());
+	assert_eq!(loaded.start, None);
+	assert!(loaded.board_options.recommended_moves().is_empty());
+}
+
+#[test]
+fn loads_every_historical_format() {
+	use strum::IntoEnumIterator;
+
+	for version in FormatVersion::iter() {
+		let json = version.fixture();
+		let value = serde_json::from_str(json).unwrap();
+		assert_eq!(FormatVersion::of(&value).unwrap(), version);
+		let loaded = UnstableSavedState::from_json(json)
+			.unwrap_or_else(|err| panic!("{} fixture: {:?}", version, err));
+		assert!(!loaded.moves.is_empty(), "{} fixture has no moves", version);
+	}
+
+	// the current format loads back exactly what was saved
+	let loaded = UnstableSavedState::from_json(FormatVersion::CURRENT.fixture()).unwrap();
+	assert_eq!(
+		UnstableSavedState::from_json(&loaded.clone().into_json()).unwrap(),
+		loaded
+	);
+
+	let err = UnstableSavedState::from_json(r#"{"version":"0.4","moves":[]}"#).unwrap_err();
+	assert!(format!("{:#}", err).contains("v0.4"), "{:#}", err);
+	assert!(UnstableSavedState::from_json(r#"{"version":"9.9"}"#).is_err());
+}
+
 mod v0_3_x {
+	use anyhow::Context;
 	use bevy::prelude::Color;
 	use derive_more::{Deref, DerefMut, From, Into};
 	use serde::{Deserialize, Serialize};
 	use serde_json_any_key::any_key_map;
-	use std::collections::HashMap;
+	use std::collections::{HashMap, HashSet};
 
+	/// Only read now, see [super::v0_4_x] for the migration
 	#[derive(Serialize, Deserialize, Debug)]
 	pub struct StableSavedState {
-		moves: self::StableColouredMoves,
-		board_options: self::StableBoardOptions,
-		metadata: super::MetaData,
+		pub moves: self::StableColouredMoves,
+		pub board_options: self::StableBoardOptions,
+		pub metadata: super::MetaData,
+	}
+
+	/// v0.2 saves didn't record targets or board size, so the board is just big enough for the moves
+	pub fn migrate_v0_2_to_v0_3(
+		mut old: super::v0_2_x::State,
+	) -> Result<StableSavedState, anyhow::Error> {
+		let reached_points: HashSet<_> = old
+			.moves
+			.moves
+			.iter()
+			.flat_map(|m| [&m.from, &m.to])
+			.collect();
+		let max_width = reached_points
+			.iter()
+			.map(|p| p.column)
+			.max()
+			.context("No moves")?;
+		let max_height = reached_points.iter().map(|p| p.row).max().unwrap();
+
+		let moves: super::ColouredMoves = old
+			.moves
+			.moves
+			.into_iter()
+			.map(|m| (m.into(), old.colours.pop().unwrap().into()))
+			.collect();
+		Ok(StableSavedState {
+			moves: moves.into(),
+			board_options: super::BoardOptions::new(max_width, max_height).into(),
+			metadata: super::MetaData::depreciated((max_width, max_height)),
+		})
 	}
 
 	#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug)]
@@ -153,35 +455,13 @@ mod v0_3_x {
 	pub struct StableColor(f32, f32, f32, f32);
 
 	#[derive(Serialize, Deserialize, Deref, DerefMut, From, Into, Debug)]
-	struct StableColouredMoves(Vec<(Point, Point, StableColor)>);
+	pub struct StableColouredMoves(Vec<(Point, Point, StableColor)>);
 
 	#[derive(Serialize, Deserialize, Deref, DerefMut, From, Into, Debug)]
 	pub struct StableBoardOptions(#[serde(with = "any_key_map")] HashMap<Point, StableCellOptions>);
 
 	// from impls
 
-	impl From<StableSavedState> for super::UnstableSavedState {
-		/// Un-stabalise the [StableSavedState]
-		fn from(value: StableSavedState) -> Self {
-			Self {
-				moves: value.moves.into(),
-				board_options: value.board_options.into(),
-				metadata: value.metadata,
-			}
-		}
-	}
-
-	impl From<super::UnstableSavedState> for StableSavedState {
-		/// Stabalise the [UnstableSavedState]
-		fn from(value: super::UnstableSavedState) -> Self {
-			Self {
-				moves: value.moves.into(),
-				board_options: value.board_options.into(),
-				metadata: value.metadata,
-			}
-		}
-	}
-
 	impl From<super::ColouredMoves> for StableColouredMoves {
 		fn from(value: super::ColouredMoves) -> Self {
 			Self(
@@ -270,6 +550,8 @@ mod v0_3_x {
 		Disabled = 0,
 		Finishable = 1,
 		NoFinishable = 2,
+		/// Only written since v0.4, older saves turned these into [Self::Disabled]
+		Eliminated = 3,
 	}
 
 	impl From<StableCellOptions> for crate::solver::CellOption {
@@ -282,6 +564,7 @@ mod v0_3_x {
 				StableCellOptions::NoFinishable => Self::Available {
 					can_finish_on: false,
 				},
+				StableCellOptions::Eliminated => Self::Eliminated,
 			}
 		}
 	}
@@ -289,7 +572,8 @@ mod v0_3_x {
 	impl From<crate::solver::CellOption> for StableCellOptions {
 		fn from(value: crate::solver::CellOption) -> Self {
 			match value {
-				crate::solver::CellOption::Unavailable | crate::solver::CellOption::Eliminated => Self::Disabled,
+				crate::solver::CellOption::Unavailable => Self::Disabled,
+				crate::solver::CellOption::Eliminated => Self::Eliminated,
 				crate::solver::CellOption::Available { can_finish_on } => {
 					if can_finish_on {
 						Self::Finishable
@@ -314,48 +598,323 @@ mod v0_3_x {
 	}
 }
 
-mod v0_2_x {
-	use std::collections::HashSet;
-
-	use anyhow::Context;
+mod v0_4_x {
 	use serde::{Deserialize, Serialize};
+	use tracing::warn;
 
-	use crate::solver::BoardOptions;
+	use super::{
+		v0_3_x::{self, Point},
+		FormatVersion,
+	};
+	use crate::{
+		board::SafteyCap,
+		solver::{
+			algs::{Algorithm, PruningRules},
+			pieces::{Piece, StandardPieces},
+			Topology,
+		},
+	};
 
-	use super::UnstableSavedState;
+	/// Also keeps the piece, solver settings, start and recommended moves, which v0.3 saves lost
+	#[derive(Serialize, Deserialize, Debug)]
+	pub struct StableSavedState {
+		/// Always [FormatVersion::V0_4], tells saves apart from older untagged ones
+		version: FormatVersion,
+		moves: v0_3_x::StableColouredMoves,
+		board_options: StableBoardOptions,
+		metadata: super::MetaData,
+		piece: StablePiece,
+		solver: StableSolverSettings,
+		start: Option<Point>,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	pub struct StableBoardOptions {
+		cells: v0_3_x::StableBoardOptions,
+		recommended_moves: Vec<(Point, Point)>,
+		topology: StableTopology,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	#[serde(rename_all = "snake_case")]
+	pub enum StableTopology {
+		Flat,
+		Cylinder,
+		Torus,
+		Mobius,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	#[serde(tag = "kind", rename_all = "snake_case")]
+	pub enum StablePiece {
+		Standard { name: StableStandardPiece },
+		AbKnight { a: i8, b: i8 },
+		Custom(StableCustomPiece),
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	pub struct StableCustomPiece {
+		name: String,
+		leaps: Vec<(i16, i16)>,
+		#[serde(default)]
+		rides: Vec<(i16, i16)>,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	#[serde(rename_all = "snake_case")]
+	pub enum StableStandardPiece {
+		Knight,
+		Camel,
+		Zebra,
+		Giraffe,
+		Wazir,
+		Ferz,
+		King,
+		Nightrider,
+		Rook,
+		Bishop,
+		Queen,
+		Amazon,
+		Archbishop,
+		Chancellor,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	pub struct StableSolverSettings {
+		/// See [Algorithm::id]
+		alg: String,
+		/// Missing for no limit
+		safety_cap: Option<u64>,
+		/// Missing for no limit
+		time_budget_secs: Option<u32>,
+		pruning: StablePruningRules,
+	}
+
+	#[derive(Serialize, Deserialize, Debug)]
+	pub struct StablePruningRules {
+		connectivity: bool,
+		dead_ends: bool,
+		unreachable_end: bool,
+	}
+
+	// from impls
 
-	/// Old didn't record targets or board size, so heuristics are added to the board options
-	pub fn try_depreciated_from_json(json: &str) -> Result<UnstableSavedState, anyhow::Error> {
-		let mut data: State = serde_json::from_str(json).context("JSON decoding failed")?;
-		let mut reached_points = HashSet::new();
-		for Move { from, to } in data.moves.moves.iter() {
-			reached_points.insert(from);
-			reached_points.insert(to);
+	impl From<StableSavedState> for super::UnstableSavedState {
+		/// Un-stabalise the [StableSavedState]
+		fn from(value: StableSavedState) -> Self {
+			let mut board_options: super::BoardOptions = value.board_options.cells.into();
+			for (from, to) in value.board_options.recommended_moves {
+				let recommended = super::Move::new(from.into(), to.into());
+				if board_options.validate_point(&recommended.from)
+					&& board_options.validate_point(&recommended.to)
+				{
+					board_options.add_recommended_move(recommended);
+				} else {
+					warn!("Skipping recommended move {} off the board", recommended);
+				}
+			}
+			board_options.set_topology(value.board_options.topology.into());
+
+			let solver = value.solver;
+			let alg = Algorithm::from_id(&solver.alg).unwrap_or_else(|| {
+				warn!(
+					"Save uses unknown solver {:?}, using the default",
+					solver.alg
+				);
+				Algorithm::default()
+			});
+			Self {
+				moves: value.moves.into(),
+				board_options,
+				metadata: value.metadata,
+				piece: value.piece.into(),
+				alg,
+				safety_cap: SafteyCap::from_limits(
+					solver.safety_cap.map(u128::from),
+					solver.time_budget_secs,
+				),
+				pruning: solver.pruning.into(),
+				start: value.start.map(Into::into),
+			}
 		}
-		let max_width = reached_points
-			.iter()
-			.map(|p| p.column)
-			.max()
-			.context("No moves")?;
-		let max_height = reached_points.iter().map(|p| p.row).max().unwrap();
+	}
 
-		let board_options = BoardOptions::new(max_width, max_height);
-		let moves = data
-			.moves
-			.moves
-			.into_iter()
-			.map(|m| (m.into(), data.colours.pop().unwrap().into()))
-			.collect();
+	impl From<super::UnstableSavedState> for StableSavedState {
+		/// Stabalise the [UnstableSavedState]
+		fn from(value: super::UnstableSavedState) -> Self {
+			let recommended_moves = value
+				.board_options
+				.recommended_moves()
+				.iter()
+				.map(|m| (m.from.into(), m.to.into()))
+				.collect();
+			let topology = value.board_options.topology().into();
+			Self {
+				version: FormatVersion::CURRENT,
+				moves: value.moves.into(),
+				board_options: StableBoardOptions {
+					cells: value.board_options.into(),
+					recommended_moves,
+					topology,
+				},
+				metadata: value.metadata,
+				piece: value.piece.into(),
+				solver: StableSolverSettings {
+					alg: value.alg.id().to_string(),
+					safety_cap: value.safety_cap.states().map(|cap| cap as u64),
+					time_budget_secs: value.safety_cap.time_budget_secs(),
+					pruning: value.pruning.into(),
+				},
+				start: value.start.map(Into::into),
+			}
+		}
+	}
 
-		Ok(UnstableSavedState {
-			moves,
-			board_options,
-			metadata: super::MetaData::depreciated((max_width, max_height)),
-		})
+	/// v0.3 saves were always solved with the default settings
+	pub fn migrate_v0_3_to_v0_4(value: v0_3_x::StableSavedState) -> StableSavedState {
+		let safety_cap = SafteyCap::default();
+		let pruning = PruningRules::default();
+		StableSavedState {
+			version: FormatVersion::V0_4,
+			moves: value.moves,
+			board_options: StableBoardOptions {
+				cells: value.board_options,
+				recommended_moves: Vec::new(),
+				topology: StableTopology::Flat,
+			},
+			metadata: value.metadata,
+			// v0.3 saves never recorded the piece, so were always made with the standard knight
+			piece: StablePiece::Standard {
+				name: StableStandardPiece::Knight,
+			},
+			solver: StableSolverSettings {
+				alg: Algorithm::default().id().to_string(),
+				safety_cap: safety_cap.states().map(|cap| cap as u64),
+				time_budget_secs: safety_cap.time_budget_secs(),
+				pruning: pruning.into(),
+			},
+			start: None,
+		}
+	}
+
+	impl From<Topology> for StableTopology {
+		fn from(value: Topology) -> Self {
+			match value {
+				Topology::Flat => Self::Flat,
+				Topology::Cylinder => Self::Cylinder,
+				Topology::Torus => Self::Torus,
+				Topology::Mobius => Self::Mobius,
+			}
+		}
+	}
+
+	impl From<StableTopology> for Topology {
+		fn from(value: StableTopology) -> Self {
+			match value {
+				StableTopology::Flat => Self::Flat,
+				StableTopology::Cylinder => Self::Cylinder,
+				StableTopology::Torus => Self::Torus,
+				StableTopology::Mobius => Self::Mobius,
+			}
+		}
+	}
+
+	impl From<Piece> for StablePiece {
+		fn from(value: Piece) -> Self {
+			let name = match value {
+				Piece::Custom(custom) => return Self::Custom(custom.into()),
+				Piece::Standard(StandardPieces::ABKnight(a, b)) => return Self::AbKnight { a, b },
+				Piece::Standard(StandardPieces::StandardKnight) => StableStandardPiece::Knight,
+				Piece::Standard(StandardPieces::Camel) => StableStandardPiece::Camel,
+				Piece::Standard(StandardPieces::Zebra) => StableStandardPiece::Zebra,
+				Piece::Standard(StandardPieces::Giraffe) => StableStandardPiece::Giraffe,
+				Piece::Standard(StandardPieces::Wazir) => StableStandardPiece::Wazir,
+				Piece::Standard(StandardPieces::Ferz) => StableStandardPiece::Ferz,
+				Piece::Standard(StandardPieces::King) => StableStandardPiece::King,
+				Piece::Standard(StandardPieces::Nightrider) => StableStandardPiece::Nightrider,
+				Piece::Standard(StandardPieces::Rook) => StableStandardPiece::Rook,
+				Piece::Standard(StandardPieces::Bishop) => StableStandardPiece::Bishop,
+				Piece::Standard(StandardPieces::Queen) => StableStandardPiece::Queen,
+				Piece::Standard(StandardPieces::Amazon) => StableStandardPiece::Amazon,
+				Piece::Standard(StandardPieces::Archbishop) => StableStandardPiece::Archbishop,
+				Piece::Standard(StandardPieces::Chancellor) => StableStandardPiece::Chancellor,
+			};
+			Self::Standard { name }
+		}
+	}
+
+	impl From<StablePiece> for Piece {
+		fn from(value: StablePiece) -> Self {
+			let standard = match value {
+				StablePiece::Custom(custom) => return Self::Custom(custom.into()),
+				StablePiece::AbKnight { a, b } => StandardPieces::ABKnight(a, b),
+				StablePiece::Standard { name } => match name {
+					StableStandardPiece::Knight => StandardPieces::StandardKnight,
+					StableStandardPiece::Camel => StandardPieces::Camel,
+					StableStandardPiece::Zebra => StandardPieces::Zebra,
+					StableStandardPiece::Giraffe => StandardPieces::Giraffe,
+					StableStandardPiece::Wazir => StandardPieces::Wazir,
+					StableStandardPiece::Ferz => StandardPieces::Ferz,
+					StableStandardPiece::King => StandardPieces::King,
+					StableStandardPiece::Nightrider => StandardPieces::Nightrider,
+					StableStandardPiece::Rook => StandardPieces::Rook,
+					StableStandardPiece::Bishop => StandardPieces::Bishop,
+					StableStandardPiece::Queen => StandardPieces::Queen,
+					StableStandardPiece::Amazon => StandardPieces::Amazon,
+					StableStandardPiece::Archbishop => StandardPieces::Archbishop,
+					StableStandardPiece::Chancellor => StandardPieces::Chancellor,
+				},
+			};
+			Self::Standard(standard)
+		}
+	}
+
+	impl From<StableCustomPiece> for super::CustomPiece {
+		fn from(value: StableCustomPiece) -> Self {
+			use crate::solver::pieces::ChessPiece;
+			Self {
+				name: value.name,
+				piece: ChessPiece::compound([ChessPiece::new(value.leaps), ChessPiece::rider(value.rides)]),
+			}
+		}
+	}
+
+	impl From<super::CustomPiece> for StableCustomPiece {
+		fn from(value: super::CustomPiece) -> Self {
+			Self {
+				name: value.name,
+				leaps: value.piece.relative_moves().clone(),
+				rides: value.piece.rides().clone(),
+			}
+		}
+	}
+
+	impl From<PruningRules> for StablePruningRules {
+		fn from(value: PruningRules) -> Self {
+			Self {
+				connectivity: value.connectivity,
+				dead_ends: value.dead_ends,
+				unreachable_end: value.unreachable_end,
+			}
+		}
 	}
 
+	impl From<StablePruningRules> for PruningRules {
+		fn from(value: StablePruningRules) -> Self {
+			Self {
+				connectivity: value.connectivity,
+				dead_ends: value.dead_ends,
+				unreachable_end: value.unreachable_end,
+			}
+		}
+	}
+}
+
+mod v0_2_x {
+	use serde::{Deserialize, Serialize};
+
 	impl super::MetaData {
-		fn depreciated(dimensions: super::Dimensions) -> super::MetaData {
+		pub(super) fn depreciated(dimensions: super::Dimensions) -> super::MetaData {
 			super::MetaData {
 				id: None,
 				title: "OLD save, no title".into(),
@@ -369,24 +928,26 @@ mod v0_2_x {
 	#[test]
 	fn data1() {
 		let data = r#"{"start":{"column":1,"row":1},"moves":{"moves":[{"from":{"column":1,"row":1},"to":{"column":2,"row":3}},{"from":{"column":2,"row":3},"to":{"column":1,"row":5}},{"from":{"column":1,"row":5},"to":{"column":3,"row":6}},{"from":{"column":3,"row":6},"to":{"column":5,"row":5}},{"from":{"column":5,"row":5},"to":{"column":4,"row":3}},{"from":{"column":4,"row":3},"to":{"column":5,"row":1}},{"from":{"column":5,"row":1},"to":{"column":3,"row":2}},{"from":{"column":3,"row":2},"to":{"column":4,"row":4}},{"from":{"column":4,"row":4},"to":{"column":5,"row":6}},{"from":{"column":5,"row":6},"to":{"column":3,"row":5}},{"from":{"column":3,"row":5},"to":{"column":1,"row":6}},{"from":{"column":1,"row":6},"to":{"column":2,"row":4}},{"from":{"column":2,"row":4},"to":{"column":1,"row":2}},{"from":{"column":1,"row":2},"to":{"column":3,"row":1}},{"from":{"column":3,"row":1},"to":{"column":5,"row":2}},{"from":{"column":5,"row":2},"to":{"column":3,"row":3}},{"from":{"column":3,"row":3},"to":{"column":5,"row":4}},{"from":{"column":5,"row":4},"to":{"column":4,"row":2}},{"from":{"column":4,"row":2},"to":{"column":2,"row":1}},{"from":{"column":2,"row":1},"to":{"column":1,"row":3}},{"from":{"column":1,"row":3},"to":{"column":2,"row":5}},{"from":{"column":2,"row":5},"to":{"column":4,"row":6}},{"from":{"column":4,"row":6},"to":{"column":3,"row":4}},{"from":{"column":3,"row":4},"to":{"column":5,"row":3}},{"from":{"column":5,"row":3},"to":{"column":4,"row":1}},{"from":{"column":4,"row":1},"to":{"column":2,"row":2}},{"from":{"column":2,"row":2},"to":{"column":1,"row":4}},{"from":{"column":1,"row":4},"to":{"column":2,"row":6}},{"from":{"column":2,"row":6},"to":{"column":4,"row":5}},{"from":{"column":4,"row":5},"to":{"column":4,"row":5}}]},"colours":["Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green","Green"]}"#;
-		let _state = try_depreciated_from_json(data).unwrap();
+		let state = super::UnstableSavedState::from_json(data).unwrap();
+		assert_eq!(state.board_options.dimensions(), (5, 6));
 	}
 
+	/// Only read now, see [super::v0_3_x] for the migration
 	#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
-	struct State {
-		moves: Moves,
-		colours: Vec<Colour>,
+	pub struct State {
+		pub moves: Moves,
+		pub colours: Vec<Colour>,
 	}
 
 	#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
-	struct Moves {
-		moves: Vec<Move>,
+	pub struct Moves {
+		pub moves: Vec<Move>,
 	}
 
 	#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
-	struct ChessPoint {
-		column: u16,
-		row: u16,
+	pub struct ChessPoint {
+		pub column: u16,
+		pub row: u16,
 	}
 
 	impl From<ChessPoint> for crate::solver::ChessPoint {
@@ -399,9 +960,9 @@ mod v0_2_x {
 	}
 
 	#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
-	struct Move {
-		from: ChessPoint,
-		to: ChessPoint,
+	pub struct Move {
+		pub from: ChessPoint,
+		pub to: ChessPoint,
 	}
 
 	impl From<Move> for crate::solver::Move {
@@ -414,7 +975,7 @@ mod v0_2_x {
 	}
 
 	#[derive(Serialize, Deserialize, PartialEq, Eq, Hash)]
-	enum Colour {
+	pub enum Colour {
 		Green,
 		Blue,
 		Red,
@@ -0,0 +1,208 @@
+//! Saves as `.cap.json` files on disk, so collections of boards can be kept in git
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use bevy::{prelude::*, window::FileDragAndDrop};
+use bevy_egui::egui::{self, Ui};
+
+use crate::{board::SharedState, ProgramState};
+
+use super::UnstableSavedState;
+
+pub const EXTENSION: &str = "cap.json";
+
+/// Gives `path` the `.cap.json` extension, replacing a plain `.json` one
+fn with_extension(path: PathBuf) -> PathBuf {
+	let name = path
+		.file_name()
+		.map(|name| name.to_string_lossy().into_owned())
+		.unwrap_or_default();
+	if name.ends_with(&format!(".{}", EXTENSION)) {
+		return path;
+	}
+	let stem = name.strip_suffix(".json").unwrap_or(&name);
+	path.with_file_name(format!("{}.{}", stem, EXTENSION))
+}
+
+pub fn write_save(path: &Path, state: UnstableSavedState) -> Result<(), anyhow::Error> {
+	std::fs::write(path, state.into_pretty_json())
+		.with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn read_save(path: &Path) -> Result<UnstableSavedState, anyhow::Error> {
+	let json =
+		std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+	UnstableSavedState::from_json(&json).with_context(|| format!("{} is not a save", path.display()))
+}
+
+/// Files most recently saved or opened, newest first.
+/// Kept in the config directory so they are remembered between runs
+#[derive(Default, Clone, Reflect)]
+pub struct RecentFiles {
+	paths: Vec<String>,
+	/// Whether [Self::paths] has been read from disk yet
+	loaded: bool,
+}
+
+impl RecentFiles {
+	const MAX: usize = 8;
+
+	fn config_file() -> Option<PathBuf> {
+		Some(
+			dirs::config_dir()?
+				.join("cap_solver")
+				.join("recent_files.json"),
+		)
+	}
+
+	fn paths(&mut self) -> &[String] {
+		if !self.loaded {
+			self.loaded = true;
+			self.paths = Self::config_file()
+				.and_then(|file| std::fs::read_to_string(file).ok())
+				.and_then(|json| serde_json::from_str(&json).ok())
+				.unwrap_or_default();
+		}
+		&self.paths
+	}
+
+	fn push(&mut self, path: &Path) {
+		let path = path.display().to_string();
+		self.paths();
+		self.paths.retain(|recent| *recent != path);
+		self.paths.insert(0, path);
+		self.paths.truncate(Self::MAX);
+
+		let Some(file) = Self::config_file() else {
+			return;
+		};
+		let written = file
+			.parent()
+			.map_or(Ok(()), std::fs::create_dir_all)
+			.and_then(|_| std::fs::write(&file, serde_json::to_string(&self.paths).unwrap()));
+		if let Err(err) = written {
+			warn!(
+				"Failed to remember recent files in {}: {}",
+				file.display(),
+				err
+			);
+		}
+	}
+}
+
+impl SharedState {
+	fn save_to_file(&mut self, path: &Path) {
+		match write_save(path, self.clone().dangerous_into()) {
+			Ok(()) => {
+				self.save_state.recent_files.push(path);
+				self.save_state.error_str = None;
+			}
+			Err(err) => {
+				self.save_state.error_str = Some(format!("{:#}", err));
+			}
+		}
+	}
+
+	pub fn load_from_file(&mut self, path: &Path) {
+		match read_save(path) {
+			Ok(state) => {
+				state.load_into(self);
+				self.save_state.recent_files.push(path);
+				self.save_state.error_str = None;
+			}
+			Err(err) => {
+				self.save_state.error_str = Some(format!("{:#}", err));
+			}
+		}
+	}
+
+	pub fn file_save_ui(&mut self, ui: &mut Ui) {
+		if self.moves.is_some() && ui.button("Save as...").clicked() {
+			let name = if self.save_state.title.is_empty() {
+				"board"
+			} else {
+				self.save_state.title.as_str()
+			};
+			if let Some(path) = rfd::FileDialog::new()
+				.add_filter("Chess board", &["json"])
+				.set_file_name(&format!("{}.{}", name, EXTENSION))
+				.save_file()
+			{
+				self.save_to_file(&with_extension(path));
+			}
+		}
+
+		if ui.button("Open...").clicked() {
+			if let Some(path) = rfd::FileDialog::new()
+				.add_filter("Chess board", &["json"])
+				.pick_file()
+			{
+				self.load_from_file(&path);
+			}
+		}
+		ui.label("Save files can also be dropped onto the window to open them");
+
+		let recent = self.save_state.recent_files.paths().to_vec();
+		if !recent.is_empty() {
+			ui.separator();
+			ui.label("Recent files:");
+		}
+		for path in recent {
+			let name = Path::new(&path)
+				.file_name()
+				.map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+			if ui.button(name).on_hover_text(path.as_str()).clicked() {
+				self.load_from_file(Path::new(&path));
+			}
+		}
+
+		if let Some(err) = &self.save_state.error_str {
+			ui.colored_label(egui::Color32::RED, err);
+		}
+	}
+
+	/// Opens save files dropped onto the window
+	pub fn sys_load_dropped_files(
+		mut dropped: EventReader<FileDragAndDrop>,
+		mut state: ResMut<SharedState>,
+		mut to_manual: ResMut<NextState<ProgramState>>,
+	) {
+		for event in dropped.iter() {
+			if let FileDragAndDrop::DroppedFile { path_buf, .. } = event {
+				info!("Loading dropped file {}", path_buf.display());
+				state.load_from_file(path_buf);
+				to_manual.set(ProgramState::Manual);
+			}
+		}
+	}
+}
+
+#[test]
+fn saves_and_loads_files() {
+	assert_eq!(
+		with_extension(PathBuf::from("boards/tour.json")),
+		PathBuf::from("boards/tour.cap.json")
+	);
+	assert_eq!(
+		with_extension(PathBuf::from("tour.cap.json")),
+		PathBuf::from("tour.cap.json")
+	);
+	assert_eq!(
+		with_extension(PathBuf::from("tour")),
+		PathBuf::from("tour.cap.json")
+	);
+
+	let saved = UnstableSavedState::from_json(super::FormatVersion::CURRENT.fixture()).unwrap();
+	// unique, so runs at the same time don't overwrite each other
+	let path = std::env::temp_dir().join(format!(
+		"saves_and_loads_files-{}.{}",
+		std::process::id(),
+		EXTENSION
+	));
+	write_save(&path, saved.clone()).unwrap();
+	assert_eq!(read_save(&path).unwrap(), saved);
+	std::fs::remove_file(&path).unwrap();
+
+	assert!(read_save(&path).is_err());
+}
@@ -0,0 +1,287 @@
+//! Where saves shared by title are kept, see [SaveStore].
+//! Firebase is the default, the others let the save/load UI and tests run without a network
+
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::Mutex,
+};
+
+use anyhow::Context;
+use once_cell::sync::Lazy;
+
+use super::{
+	file::{read_save, write_save, EXTENSION},
+	firebase::{FirebaseStore, ID},
+	MetaData, UnstableSavedState,
+};
+
+/// The store picked by [StoreConfig::from_env]
+pub static STORE: Lazy<Box<dyn SaveStore>> = Lazy::new(|| StoreConfig::from_env().open());
+
+pub trait SaveStore: Send + Sync {
+	/// Adds a new save, returning its id
+	fn save(&self, state: UnstableSavedState) -> Result<ID, anyhow::Error>;
+
+	fn load(&self, id: &ID) -> Result<UnstableSavedState, anyhow::Error>;
+
+	/// Metadata of every save, with their ids filled in.
+	/// Saves that can't be read are left out, rather than hiding all the others
+	fn list_metadata(&self) -> Result<Vec<MetaData>, anyhow::Error>;
+
+	fn delete(&self, id: &ID) -> Result<(), anyhow::Error>;
+
+	/// Replaces the save with `id`, which must already exist
+	fn update(&self, id: &ID, state: UnstableSavedState) -> Result<(), anyhow::Error>;
+
+	/// Link to view the save in the browser, if the web version can load from this store
+	fn web_url(&self, _id: &ID) -> Option<String> {
+		None
+	}
+
+	/// Whether only this user can see the saves, so the UI can offer to overwrite and delete them.
+	/// Saves in a shared store belong to everyone, so are left alone
+	fn is_private(&self) -> bool {
+		false
+	}
+}
+
+/// Which [SaveStore] to use, read from the `CAP_SAVE_STORE` environment variable:
+/// `firebase`, `memory` or `dir:<path>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StoreConfig {
+	#[default]
+	Firebase,
+	InMemory,
+	LocalDir(PathBuf),
+}
+
+impl StoreConfig {
+	pub const ENV_VAR: &'static str = "CAP_SAVE_STORE";
+
+	/// Falls back to [StoreConfig::Firebase] if the variable isn't set or can't be parsed
+	pub fn from_env() -> Self {
+		let Ok(config) = std::env::var(Self::ENV_VAR) else {
+			return Self::default();
+		};
+		config.parse().unwrap_or_else(|err| {
+			tracing::warn!("Ignoring {}: {:#}", Self::ENV_VAR, err);
+			Self::default()
+		})
+	}
+
+	pub fn open(self) -> Box<dyn SaveStore> {
+		match self {
+			Self::Firebase => Box::new(FirebaseStore),
+			Self::InMemory => Box::<InMemoryStore>::default(),
+			Self::LocalDir(dir) => Box::new(LocalDirStore::new(dir)),
+		}
+	}
+}
+
+impl FromStr for StoreConfig {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"firebase" => Ok(Self::Firebase),
+			"memory" => Ok(Self::InMemory),
+			_ => match s.strip_prefix("dir:") {
+				Some(dir) if !dir.is_empty() => Ok(Self::LocalDir(dir.into())),
+				_ => Err(anyhow::anyhow!(
+					"Unknown save store {:?}, expected firebase, memory or dir:<path>",
+					s
+				)),
+			},
+		}
+	}
+}
+
+/// Forgotten when the program exits
+#[derive(Default)]
+pub struct InMemoryStore {
+	saves: Mutex<HashMap<ID, UnstableSavedState>>,
+}
+
+impl SaveStore for InMemoryStore {
+	fn save(&self, mut state: UnstableSavedState) -> Result<ID, anyhow::Error> {
+		let id = ID::new();
+		state.metadata.id = Some(id.clone());
+		self.saves.lock().unwrap().insert(id.clone(), state);
+		Ok(id)
+	}
+
+	fn load(&self, id: &ID) -> Result<UnstableSavedState, anyhow::Error> {
+		self
+			.saves
+			.lock()
+			.unwrap()
+			.get(id)
+			.cloned()
+			.with_context(|| format!("No save with id {}", id.inner()))
+	}
+
+	fn list_metadata(&self) -> Result<Vec<MetaData>, anyhow::Error> {
+		let saves = self.saves.lock().unwrap();
+		Ok(saves.values().map(|state| state.metadata.clone()).collect())
+	}
+
+	fn delete(&self, id: &ID) -> Result<(), anyhow::Error> {
+		self
+			.saves
+			.lock()
+			.unwrap()
+			.remove(id)
+			.map(|_| ())
+			.with_context(|| format!("No save with id {}", id.inner()))
+	}
+
+	fn update(&self, id: &ID, mut state: UnstableSavedState) -> Result<(), anyhow::Error> {
+		let mut saves = self.saves.lock().unwrap();
+		let saved = saves
+			.get_mut(id)
+			.with_context(|| format!("No save with id {}", id.inner()))?;
+		state.metadata.id = Some(id.clone());
+		*saved = state;
+		Ok(())
+	}
+
+	fn is_private(&self) -> bool {
+		true
+	}
+}
+
+/// Every save is a `<id>.cap.json` file in one directory, like those saved with "Save as..."
+pub struct LocalDirStore {
+	dir: PathBuf,
+}
+
+impl LocalDirStore {
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	fn path(&self, id: &ID) -> PathBuf {
+		self.dir.join(format!("{}.{}", id.inner(), EXTENSION))
+	}
+
+	fn id_of(path: &Path) -> Option<ID> {
+		let name = path.file_name()?.to_str()?;
+		let id = name.strip_suffix(&format!(".{}", EXTENSION))?;
+		Some(ID::from(id.to_string()))
+	}
+}
+
+impl SaveStore for LocalDirStore {
+	fn save(&self, state: UnstableSavedState) -> Result<ID, anyhow::Error> {
+		std::fs::create_dir_all(&self.dir)
+			.with_context(|| format!("Failed to create {}", self.dir.display()))?;
+		let id = ID::new();
+		write_save(&self.path(&id), state)?;
+		Ok(id)
+	}
+
+	fn load(&self, id: &ID) -> Result<UnstableSavedState, anyhow::Error> {
+		let mut state = read_save(&self.path(id))?;
+		state.metadata.id = Some(id.clone());
+		Ok(state)
+	}
+
+	fn list_metadata(&self) -> Result<Vec<MetaData>, anyhow::Error> {
+		if !self.dir.exists() {
+			return Ok(Vec::new());
+		}
+		let entries = std::fs::read_dir(&self.dir)
+			.with_context(|| format!("Failed to read {}", self.dir.display()))?;
+		let mut metadatas = Vec::new();
+		for entry in entries {
+			let path = entry?.path();
+			let Some(id) = Self::id_of(&path) else {
+				continue;
+			};
+			match read_save(&path) {
+				Ok(state) => metadatas.push(MetaData {
+					id: Some(id),
+					..state.metadata
+				}),
+				Err(err) => tracing::warn!("Skipping {}: {:#}", path.display(), err),
+			}
+		}
+		Ok(metadatas)
+	}
+
+	fn delete(&self, id: &ID) -> Result<(), anyhow::Error> {
+		let path = self.path(id);
+		std::fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))
+	}
+
+	fn update(&self, id: &ID, state: UnstableSavedState) -> Result<(), anyhow::Error> {
+		let path = self.path(id);
+		if !path.exists() {
+			anyhow::bail!("No save with id {}", id.inner());
+		}
+		write_save(&path, state)
+	}
+
+	fn is_private(&self) -> bool {
+		true
+	}
+}
+
+#[test]
+fn offline_stores_save_load_and_delete() {
+	assert_eq!(
+		"memory".parse::<StoreConfig>().unwrap(),
+		StoreConfig::InMemory
+	);
+	assert_eq!(
+		"dir:saves".parse::<StoreConfig>().unwrap(),
+		StoreConfig::LocalDir("saves".into())
+	);
+	assert!("dir:".parse::<StoreConfig>().is_err());
+	assert!("ftp".parse::<StoreConfig>().is_err());
+
+	// unique, so runs at the same time don't share saves
+	let dir = std::env::temp_dir().join(format!(
+		"offline_stores_save_load_and_delete-{}",
+		std::process::id()
+	));
+	let _ = std::fs::remove_dir_all(&dir);
+	for store in [
+		StoreConfig::InMemory.open(),
+		StoreConfig::LocalDir(dir.clone()).open(),
+	] {
+		let saved = UnstableSavedState::from_json(super::FormatVersion::CURRENT.fixture()).unwrap();
+		assert!(store.list_metadata().unwrap().is_empty());
+
+		let id = store.save(saved.clone()).unwrap();
+		let mut loaded = store.load(&id).unwrap();
+		assert_eq!(loaded.metadata.id, Some(id.clone()));
+		loaded.metadata.id = None;
+		assert_eq!(loaded, saved);
+
+		let mut updated = saved.clone();
+		updated.metadata.title = "Updated".into();
+		store.update(&id, updated).unwrap();
+		let listed = store.list_metadata().unwrap();
+		assert_eq!(listed.len(), 1);
+		assert_eq!(listed[0].id, Some(id.clone()));
+		assert_eq!(listed[0].title, "Updated");
+
+		store.delete(&id).unwrap();
+		assert!(store.load(&id).is_err());
+		assert!(store.update(&id, saved).is_err());
+		assert!(store.list_metadata().unwrap().is_empty());
+	}
+
+	let store = LocalDirStore::new(&dir);
+	let id = store
+		.save(UnstableSavedState::from_json(super::FormatVersion::CURRENT.fixture()).unwrap())
+		.unwrap();
+	std::fs::write(dir.join(format!("broken.{}", EXTENSION)), "not a save").unwrap();
+	let listed = store.list_metadata().unwrap();
+	assert_eq!(listed.len(), 1);
+	assert_eq!(listed[0].id, Some(id));
+	std::fs::remove_dir_all(&dir).unwrap();
+}
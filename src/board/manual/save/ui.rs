@@ -6,7 +6,8 @@ use bevy_egui::egui::Widget;
 
 use crate::board::SharedState;
 
-use super::firebase;
+#[cfg(not(target_arch = "wasm32"))]
+use super::store::STORE;
 use super::MetaData;
 use super::UnstableSavedState;
 
@@ -19,6 +20,8 @@ pub struct SaveState {
 	pub error_str: Option<String>,
 	pub loaded_metadatas: Vec<MetaData>,
 	pub is_typing: bool,
+	#[cfg(not(target_arch = "wasm32"))]
+	pub recent_files: super::file::RecentFiles,
 }
 
 impl TryFrom<SharedState> for super::MetaData {
@@ -58,8 +61,7 @@ impl SharedState {
 		if ui.button("Load from clipboard (all versions)").clicked() {
 			let json = crate::clipboard::get_from_clipboard();
 			if let Ok(state) = UnstableSavedState::from_json(json.trim()) {
-				self.moves = Some(state.moves);
-				self.board_options = state.board_options;
+				state.load_into(self);
 			};
 		}
 		ui.label("This can load older saves.");
@@ -71,7 +73,9 @@ impl SharedState {
 		if ui.button("Save to DB (>= v0.3").clicked() {
 			match UnstableSavedState::try_from(self.clone()) {
 				Ok(state) => {
-					firebase::save_to_db(state);
+					if let Err(err) = STORE.save(state) {
+						self.save_state.error_str = Some(format!("{:#}", err));
+					}
 				}
 				Err(err) => {
 					self.save_state.error_str = Some(err);
@@ -105,38 +109,61 @@ impl SharedState {
 
 	fn new_load_ui(&mut self, ui: &mut Ui) {
 		if ui.button("Load list of saves").clicked() {
-			match firebase::get_metadata_list() {
-				Some(list) => {
-					self.save_state.loaded_metadatas = list;
-				}
-				None => {
-					self.save_state.error_str = Some("Failed to get list of saves".to_string());
-				}
-			}
+			self.load_metadata_list();
 		}
 
-		for metadata in &self.save_state.loaded_metadatas {
+		for metadata in self.save_state.loaded_metadatas.clone() {
+			let id = metadata.id.clone().unwrap();
 			if ui.button(metadata.title.clone()).clicked() {
-				match firebase::get_from_db(metadata.id.clone().unwrap()) {
-					Some(state) => {
-						self.moves = Some(state.moves);
-						self.board_options = state.board_options;
+				match STORE.load(&id) {
+					Ok(state) => {
+						state.load_into(self);
 					}
-					None => {
-						self.save_state.error_str = Some("Failed to load save".to_string());
+					Err(err) => {
+						self.save_state.error_str = Some(format!("{:#}", err));
 					}
 				}
 			}
+			// other people's saves in a shared store can't be overwritten or deleted
+			if STORE.is_private() {
+				ui.horizontal(|ui| {
+					if self.moves.is_some() && ui.button("Overwrite with this board").clicked() {
+						let mut state = self.clone().dangerous_into();
+						state.metadata = metadata.clone();
+						state.metadata.dimensions = self.board_options.dimensions();
+						if let Err(err) = STORE.update(&id, state) {
+							self.save_state.error_str = Some(format!("{:#}", err));
+						}
+						self.load_metadata_list();
+					}
+					if ui.button("Delete").clicked() {
+						if let Err(err) = STORE.delete(&id) {
+							self.save_state.error_str = Some(format!("{:#}", err));
+						}
+						self.load_metadata_list();
+					}
+				});
+			}
 			ui.label(format!("By: {}", metadata.author));
 			ui.label(format!(
 				"Dimensions: {}x{}",
 				metadata.dimensions.0, metadata.dimensions.1
 			));
 			ui.label(format!("Description: {}", metadata.description));
-			ui.hyperlink_to(
-				"Click to open in browser",
-				crate::weburl::create_url_with_id(metadata.id.clone().unwrap().inner().into()),
-			);
+			if let Some(url) = STORE.web_url(&id) {
+				ui.hyperlink_to("Click to open in browser", url);
+			}
+		}
+	}
+
+	fn load_metadata_list(&mut self) {
+		match STORE.list_metadata() {
+			Ok(list) => {
+				self.save_state.loaded_metadatas = list;
+			}
+			Err(err) => {
+				self.save_state.error_str = Some(format!("{:#}", err));
+			}
 		}
 	}
 }
@@ -144,6 +171,12 @@ impl SharedState {
 	pub fn save_ui(&mut self, ui: &mut Ui) {
 		#[cfg(not(target_arch = "wasm32"))]
 		{
+			egui::CollapsingHeader::new("Save/Open files")
+				.default_open(true)
+				.show(ui, |ui| {
+					self.file_save_ui(ui);
+				});
+
 			egui::CollapsingHeader::new("Old Save/Load")
 				.default_open(false)
 				.show(ui, |ui| {
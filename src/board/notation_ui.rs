@@ -0,0 +1,49 @@
+//! Copying and pasting tours as squares like `a1 c2 e1`, see [crate::solver::notation]
+
+use bevy_egui::egui::{Color32, Ui};
+
+use crate::solver::{notation::write_tour, pieces::ChessPiece};
+
+use super::*;
+
+#[derive(Default, Clone, Reflect, FromReflect)]
+pub struct NotationState {
+	pub error_str: Option<String>,
+}
+
+impl SharedState {
+	pub fn notation_ui(&mut self, ui: &mut Ui) {
+		if let Some(moves) = &self.moves {
+			if ui.button("Copy tour as notation").clicked() {
+				let text = write_tour(&moves.moves());
+				ui.output_mut(|out| {
+					out.copied_text = text;
+				});
+			}
+		}
+
+		#[cfg(not(target_arch = "wasm32"))]
+		if ui.button("Import tour from clipboard").clicked() {
+			let text = crate::clipboard::get_from_clipboard();
+			match ColouredMoves::from_notation(
+				&text,
+				&ChessPiece::from(&self.piece),
+				&self.board_options,
+				self.viz_colour,
+			) {
+				Ok(moves) => {
+					self.start = moves.first().map(|(m, _)| m.from);
+					self.moves = Some(moves);
+					self.notation.error_str = None;
+				}
+				Err(err) => {
+					self.notation.error_str = Some(err.to_string());
+				}
+			}
+		}
+		ui.label("Squares are separated by spaces, and / starts a new piece of the tour");
+		if let Some(err) = &self.notation.error_str {
+			ui.colored_label(Color32::RED, err);
+		}
+	}
+}
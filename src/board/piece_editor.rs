@@ -0,0 +1,201 @@
+//! Making a [CustomPiece] by clicking the offsets it can leap to on a small grid around it
+
+use bevy_egui::egui::{self, Color32, RichText, TextEdit, Ui, Vec2, Widget};
+
+use crate::solver::pieces::{symmetric_images, ChessPiece, CustomPiece, Piece};
+
+use super::*;
+
+#[derive(Clone, Reflect, FromReflect)]
+pub struct PieceEditor {
+	pub name: String,
+	/// Offsets the piece being edited can leap to
+	leaps: Vec<(i16, i16)>,
+	/// How far from the piece the grid goes
+	radius: i16,
+	/// Toggling an offset also toggles its reflections
+	mirror: bool,
+	/// Toggling an offset also toggles it turned by quarter turns
+	rotate: bool,
+
+	/// Every custom piece made or loaded so far
+	pub library: Vec<CustomPiece>,
+	pub error_str: Option<String>,
+	pub is_typing: bool,
+}
+
+impl Default for PieceEditor {
+	fn default() -> Self {
+		Self {
+			name: String::new(),
+			leaps: ChessPiece::default().relative_moves().clone(),
+			radius: 3,
+			mirror: true,
+			rotate: true,
+			library: Vec::new(),
+			error_str: None,
+			is_typing: false,
+		}
+	}
+}
+
+impl PieceEditor {
+	const MAX_RADIUS: i16 = 5;
+	const CELL_SIZE: f32 = 18.;
+
+	/// Adds `custom` to the library, replacing any piece with the same name
+	pub fn remember(&mut self, custom: CustomPiece) {
+		self.library.retain(|saved| saved.name != custom.name);
+		self.library.push(custom);
+	}
+
+	fn edit(&mut self, custom: &CustomPiece) {
+		self.name = custom.name.clone();
+		self.leaps = custom.piece.relative_moves().clone();
+	}
+
+	fn toggle(&mut self, d: (i16, i16)) {
+		let images = symmetric_images(d, self.mirror, self.rotate);
+		if self.leaps.contains(&d) {
+			self.leaps.retain(|leap| !images.contains(leap));
+		} else {
+			for image in images {
+				if !self.leaps.contains(&image) {
+					self.leaps.push(image);
+				}
+			}
+		}
+	}
+
+	fn grid_ui(&mut self, ui: &mut Ui) {
+		let radius = self.radius;
+		egui::Grid::new("Piece editor grid")
+			.spacing([2., 2.])
+			.show(ui, |ui| {
+				// rows are numbered from the bottom of the board
+				for dx in (-radius..=radius).rev() {
+					for dy in -radius..=radius {
+						if (dx, dy) == (0, 0) {
+							ui.add_sized([Self::CELL_SIZE; 2], egui::Label::new("•"));
+							continue;
+						}
+						let fill = if self.leaps.contains(&(dx, dy)) {
+							Color32::GREEN
+						} else if (dx + dy) % 2 == 0 {
+							Color32::DARK_GRAY
+						} else {
+							Color32::GRAY
+						};
+						if egui::Button::new("")
+							.fill(fill)
+							.min_size(Vec2::splat(Self::CELL_SIZE))
+							.ui(ui)
+							.on_hover_text(format!("({}, {})", dx, dy))
+							.clicked()
+						{
+							self.toggle((dx, dy));
+						}
+					}
+					ui.end_row();
+				}
+			});
+	}
+
+	pub fn ui(&mut self, ui: &mut Ui, piece: &mut Piece) -> StateInvalidated {
+		let mut invalidate = StateInvalidated::Valid;
+
+		if ui.button("Start from current piece").clicked() {
+			self.leaps = ChessPiece::from(&*piece).relative_moves().clone();
+			if let Piece::Custom(custom) = piece {
+				self.name = custom.name.clone();
+			}
+		}
+		ui.label("Only leaps can be edited, riders like the Rook lose their slides");
+
+		ui.add(egui::Slider::new(&mut self.radius, 1..=Self::MAX_RADIUS).text("Grid size"));
+		ui.horizontal(|ui| {
+			ui.checkbox(&mut self.mirror, "Mirror");
+			ui.checkbox(&mut self.rotate, "Rotate");
+			if ui.button("Clear").clicked() {
+				self.leaps.clear();
+			}
+		});
+		self.grid_ui(ui);
+
+		self.is_typing = false;
+		ui.label("Name:");
+		if TextEdit::singleline(&mut self.name)
+			.hint_text("Camel knight")
+			.ui(ui)
+			.has_focus()
+		{
+			self.is_typing = true;
+		}
+
+		if ui.button("Use this piece").clicked() {
+			if self.name.is_empty() {
+				self.error_str = Some("No name specified".to_string());
+			} else if self.leaps.is_empty() {
+				self.error_str = Some("The piece has no moves".to_string());
+			} else {
+				let custom = CustomPiece {
+					name: self.name.clone(),
+					piece: ChessPiece::new(self.leaps.clone()),
+				};
+				self.remember(custom.clone());
+				*piece = Piece::Custom(custom);
+				self.error_str = None;
+				invalidate = StateInvalidated::Invalidated;
+			}
+		}
+		if let Some(err) = &self.error_str {
+			ui.colored_label(Color32::RED, err);
+		}
+
+		ui.separator();
+		ui.label("Saved pieces:");
+		let mut chosen = None;
+		for custom in self.library.iter() {
+			ui.horizontal(|ui| {
+				let is_used = matches!(&*piece, Piece::Custom(used) if used == custom);
+				let text = RichText::new(custom.name.clone()).color(if is_used {
+					Color32::GREEN
+				} else {
+					Color32::GRAY
+				});
+				if ui.button(text).clicked() {
+					chosen = Some(custom.clone());
+				}
+				#[cfg(not(target_arch = "wasm32"))]
+				if ui.button("Copy").clicked() {
+					let json = serde_json::to_string(custom).expect("Cannot serialise piece");
+					ui.output_mut(|out| {
+						out.copied_text = json;
+					});
+				}
+			});
+		}
+		if let Some(custom) = chosen {
+			self.edit(&custom);
+			*piece = Piece::Custom(custom);
+			invalidate = StateInvalidated::Invalidated;
+		}
+
+		#[cfg(not(target_arch = "wasm32"))]
+		if ui.button("Load piece from clipboard").clicked() {
+			let json = crate::clipboard::get_from_clipboard();
+			match serde_json::from_str::<CustomPiece>(json.trim()) {
+				Ok(custom) => {
+					self.edit(&custom);
+					self.remember(custom);
+					self.error_str = None;
+				}
+				Err(err) => {
+					self.error_str = Some(format!("Not a copied piece: {}", err));
+				}
+			}
+		}
+
+		invalidate
+	}
+}
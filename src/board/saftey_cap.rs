@@ -1,34 +1,106 @@
+use std::time::Duration;
+
 use bevy::reflect::{Reflect, FromReflect};
 use bevy_egui::egui::{self, Ui};
-use derive_more::Into;
 
-#[derive(PartialEq, Eq, Clone, Into, Reflect, FromReflect)]
+/// Limits on how long a computation may run, see [crate::solver::algs::Limits].
+/// At least one of the limits is always enabled
+#[derive(PartialEq, Eq, Clone, Debug, Reflect, FromReflect)]
 pub struct SafteyCap {
 	/// Maximum amount of states considered before giving up
 	cap: u128,
+	limit_states: bool,
+	/// Maximum seconds spent before giving up
+	time_budget_secs: u32,
+	limit_time: bool,
 }
 
 const MIN: u128 = 10;
 const MAX: u128 = 1000000;
 const DEFAULT: u128 = 6969;
 
+const MIN_SECS: u32 = 1;
+const MAX_SECS: u32 = 600;
+const DEFAULT_SECS: u32 = 10;
+
 impl SafteyCap {
+	pub fn states(&self) -> Option<u128> {
+		self.limit_states.then_some(self.cap)
+	}
+
+	pub fn time_budget(&self) -> Option<Duration> {
+		self
+			.limit_time
+			.then(|| Duration::from_secs(self.time_budget_secs as u64))
+	}
+
+	pub fn time_budget_secs(&self) -> Option<u32> {
+		self.limit_time.then_some(self.time_budget_secs)
+	}
+
+	/// As returned by [Self::states] and [Self::time_budget_secs], clamped to what the UI allows.
+	/// Falls back to the default if neither is set
+	pub fn from_limits(states: Option<u128>, time_budget_secs: Option<u32>) -> Self {
+		if states.is_none() && time_budget_secs.is_none() {
+			return Self::default();
+		}
+		let default = Self::default();
+		Self {
+			cap: states.map_or(default.cap, |cap| cap.clamp(MIN, MAX)),
+			limit_states: states.is_some(),
+			time_budget_secs: time_budget_secs.map_or(default.time_budget_secs, |secs| {
+				secs.clamp(MIN_SECS, MAX_SECS)
+			}),
+			limit_time: time_budget_secs.is_some(),
+		}
+	}
+
 	pub fn ui(&mut self, ui: &mut Ui) {
-		ui.add(
-			egui::Slider::from_get_set((MIN as f64)..=(MAX as f64), |val| {
-				if let Some(val) = val {
-					self.cap = val as u128;
-				}
-				self.cap as f64
-			})
-			.text("Safety cap")
-			.logarithmic(true),
-		);
+		ui.horizontal(|ui| {
+			// can't disable both
+			ui.add_enabled(
+				self.limit_time,
+				egui::Checkbox::new(&mut self.limit_states, ""),
+			);
+			ui.add_enabled(
+				self.limit_states,
+				egui::Slider::from_get_set((MIN as f64)..=(MAX as f64), |val| {
+					if let Some(val) = val {
+						self.cap = val as u128;
+					}
+					self.cap as f64
+				})
+				.text("Safety cap")
+				.logarithmic(true),
+			);
+		});
+		ui.horizontal(|ui| {
+			ui.add_enabled(
+				self.limit_states,
+				egui::Checkbox::new(&mut self.limit_time, ""),
+			);
+			ui.add_enabled(
+				self.limit_time,
+				egui::Slider::from_get_set((MIN_SECS as f64)..=(MAX_SECS as f64), |val| {
+					if let Some(val) = val {
+						self.time_budget_secs = val as u32;
+					}
+					self.time_budget_secs as f64
+				})
+				.text("Time limit (s)")
+				.logarithmic(true),
+			);
+		});
 	}
 }
 
 impl Default for SafteyCap {
 	fn default() -> Self {
-		Self { cap: DEFAULT }
+		Self {
+			cap: DEFAULT,
+			limit_states: true,
+			time_budget_secs: DEFAULT_SECS,
+			limit_time: false,
+		}
 	}
 }
@@ -0,0 +1,80 @@
+//! Links that reproduce the board on their own, see [crate::weburl::SharedBoard]
+
+use bevy_egui::egui::{Color32, Ui};
+use strum::IntoEnumIterator;
+
+use crate::weburl::{self, SharedBoard};
+
+use super::*;
+
+impl SharedState {
+	fn share_board(&self) -> SharedBoard {
+		let colours: Vec<VizColour> = VizColour::iter().collect();
+		SharedBoard {
+			board_options: self.board_options.clone(),
+			piece: self.piece.clone(),
+			start: self.start,
+			moves: self
+				.moves
+				.iter()
+				.flat_map(|moves| moves.iter())
+				.map(|(m, colour)| {
+					let index = colours.iter().position(|c| c == colour).unwrap();
+					(*m, index as u8)
+				})
+				.collect(),
+		}
+	}
+
+	pub fn load_shared_board(&mut self, board: SharedBoard) {
+		if let Piece::Custom(custom) = &board.piece {
+			self.piece_editor.remember(custom.clone());
+		}
+		self.board_options = board.board_options;
+		self.piece = board.piece;
+		self.start = board.start;
+		self.moves = (!board.moves.is_empty()).then(|| {
+			board
+				.moves
+				.into_iter()
+				.map(|(m, colour)| {
+					(
+						m,
+						VizColour::iter().nth(colour as usize).unwrap_or_default(),
+					)
+				})
+				.collect()
+		});
+	}
+
+	pub fn share_link_ui(&mut self, ui: &mut Ui) {
+		if ui.button("Copy share link").clicked() {
+			let url = weburl::create_url_with_board(&self.share_board());
+			ui.output_mut(|out| {
+				out.copied_text = url;
+			});
+		}
+		ui.label("The link holds the whole board, so it opens without the database");
+
+		#[cfg(not(target_arch = "wasm32"))]
+		if ui.button("Open share link from clipboard").clicked() {
+			let url = crate::clipboard::get_from_clipboard();
+			match weburl::extract_url(url.trim()) {
+				Some(weburl::UrlData::Board(board)) => {
+					self.load_shared_board(board);
+					self.share_link_error = None;
+				}
+				Some(weburl::UrlData::Id(_)) => {
+					self.share_link_error =
+						Some("Links to the database can be opened from the DB list".to_string());
+				}
+				None => {
+					self.share_link_error = Some("Not a share link".to_string());
+				}
+			}
+		}
+		if let Some(err) = &self.share_link_error {
+			ui.colored_label(Color32::RED, err);
+		}
+	}
+}
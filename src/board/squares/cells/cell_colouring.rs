@@ -3,18 +3,15 @@ use std::{
 	sync::Mutex,
 };
 
-use crate::solver::Move;
 use bevy::{prelude::*, reflect::FromReflect};
 use bevy_egui::egui::{epaint::Hsva, Rgba, Ui};
 use once_cell::sync::Lazy;
-use petgraph::{
-	prelude::UnGraph,
-	visit::Bfs,
-};
+use petgraph::visit::Bfs;
 use strum::EnumIs;
 
 use crate::{
 	solver::{
+		move_graph::move_graph,
 		pieces::ChessPiece,
 		BoardOptions,
 	},
@@ -133,30 +130,12 @@ fn set(key: Key, val: Val) {
 
 /// Uses BFS to colour all cells connected by any number of knights moves the same colour
 fn compute_colourings(input: &ComputeInput) -> Val {
-	let piece = &input.piece;
 	let start = input.start;
-	let mut graph = UnGraph::<ChessPoint, Move>::new_undirected();
-
-	let mut available_points = HashMap::new();
-	for point in input.board_options.get_available_points() {
-		available_points.insert(point, graph.add_node(point));
-	}
-
-	for available_point in input.board_options.get_available_points() {
-		let available_point_index = *available_points.get(&available_point).unwrap();
-		for point in input
-			.board_options
-			.get_valid_adjacent_points(available_point, piece)
-		{
-			// info!("Checking from {} to adjacent {}, available_points contains {:?}", available_point, point, available_points.get(&point));
-			let point_index = *available_points.get(&point).unwrap();
-			graph.add_edge(
-				available_point_index,
-				point_index,
-				Move::new(available_point, point),
-			);
-		}
-	}
+	let graph = move_graph(&input.board_options, &input.piece);
+	let available_points: HashMap<ChessPoint, _> = graph
+		.node_indices()
+		.map(|index| (graph[index], index))
+		.collect();
 
 	let mut all_points = input
 		.board_options
@@ -233,7 +212,7 @@ impl TryFrom<&BorrowedCellsState<'_>> for ComputeInput {
 		Ok(Self {
 			board_options,
 			start,
-			piece: (*state.piece).into(),
+			piece: state.piece.into(),
 		})
 	}
 }
@@ -31,7 +31,7 @@ mod cells_state {
 		pub moves: &'shared Option<ColouredMoves>,
 		pub start: &'shared Option<ChessPoint>,
 		pub cell_colouring: &'shared CellColouring,
-		pub piece: &'shared StandardPieces,
+		pub piece: &'shared Piece,
 	}
 
 	/// Used to store for later comparisons
@@ -80,6 +80,16 @@ mod cells_state {
 
 static PREVIOUS_RENDER: Mutex<Option<OwnedCellsState>> = Mutex::new(None);
 impl SharedState {
+	/// The colour every cell is drawn with, see [CellColouring::compute_colour]
+	pub fn cell_colours(&self) -> Vec<(ChessPoint, Color)> {
+		let state = BorrowedCellsState::new(self);
+		self
+			.get_all_points()
+			.into_iter()
+			.map(|point| (point, self.cell_colouring.compute_colour(&point, &state)))
+			.collect()
+	}
+
 	pub fn sys_render_cells(
 		state: Res<SharedState>,
 
@@ -3,6 +3,11 @@ use super::*;
 /// Returns spacial coordinates of center of cell mesh
 fn get_spacial_coord_normalized(board: &BoardOptions, chess_position: ChessPoint) -> Vec2 {
 	let ChessPoint { row: y, column: x } = chess_position;
+	get_spacial_coord_normalized_unbounded(board, (y as i32, x as i32))
+}
+
+/// Also works for positions off the board
+fn get_spacial_coord_normalized_unbounded(board: &BoardOptions, (y, x): (i32, i32)) -> Vec2 {
 	let width = board.width() as f32;
 	let height = board.height() as f32;
 	let x = x as f32;
@@ -30,6 +35,14 @@ pub fn get_spacial_coord_2d(board: &BoardOptions, chess_position: ChessPoint) ->
 	Vec2::new(normalized.x, -normalized.y)
 }
 
+/// Like [get_spacial_coord_2d], for `(row, column)` positions off the board,
+/// such as where a move would land if it didn't wrap around the board
+pub fn get_spacial_coord_2d_unbounded(board: &BoardOptions, position: (i32, i32)) -> Vec2 {
+	let normalized = get_spacial_coord_normalized_unbounded(board, position) * CELL_SIZE;
+
+	Vec2::new(normalized.x, -normalized.y)
+}
+
 pub fn cell_get_transform(at: ChessPoint, options: &BoardOptions) -> Transform {
 	Transform::from_translation(get_spacial_coord(options, at))
 		.with_rotation(Quat::from_rotation_x(-TAU / 4.))
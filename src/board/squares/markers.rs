@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use crate::solver::algs::Computation;
+use crate::solver::algs::{Computation, Precheck};
 
 use super::*;
 
@@ -61,8 +61,9 @@ mod markers_state {
 		pub visual_opts: &'shared VisualOpts,
 		pub alg: &'shared Algorithm,
 		pub start: &'shared Option<ChessPoint>,
-		pub piece: &'shared StandardPieces,
+		pub piece: &'shared Piece,
 		pub safety_cap: &'shared SafteyCap,
+		pub pruning: &'shared PruningRules,
 	}
 
 	/// Used to store for later comparisons
@@ -72,8 +73,9 @@ mod markers_state {
 		pub visual_opts: VisualOpts,
 		pub alg: Algorithm,
 		pub start: Option<ChessPoint>,
-		pub piece: StandardPieces,
+		pub piece: Piece,
 		pub safety_cap: SafteyCap,
+		pub pruning: PruningRules,
 	}
 
 	impl<'shared> BorrowedMarkersState<'shared> {
@@ -85,6 +87,7 @@ mod markers_state {
 				start: &state.start,
 				piece: &state.piece,
 				safety_cap: &state.safety_cap,
+				pruning: &state.pruning,
 			}
 		}
 
@@ -93,8 +96,10 @@ mod markers_state {
 				alg: *self.alg,
 				start,
 				board_options: self.board_options.clone(),
-				piece: (*self.piece).into(),
-				safety_cap: self.safety_cap.clone().into(),
+				piece: self.piece.into(),
+				safety_cap: self.safety_cap.states(),
+				time_budget: self.safety_cap.time_budget(),
+				pruning: *self.pruning,
 			}
 		}
 	}
@@ -108,6 +113,7 @@ mod markers_state {
 				start: state.start,
 				piece: state.piece,
 				safety_cap: state.safety_cap,
+				pruning: state.pruning,
 			}
 		}
 
@@ -119,6 +125,7 @@ mod markers_state {
 				start: &self.start,
 				piece: &self.piece,
 				safety_cap: &self.safety_cap,
+				pruning: &self.pruning,
 			}
 		}
 	}
@@ -133,10 +140,17 @@ mod markers_state {
 }
 
 fn spawn_markers(state: &BorrowedMarkersState, commands: &mut Commands, mma: &mut ResSpawning) {
+	// the same for every start, so only worked out once
+	let precheck = Precheck::new(
+		state.board_options,
+		&state.piece.into(),
+		state.alg.tour_kind(),
+	);
 	for point in state.get_all_points() {
 		spawn_mark(
 			point,
 			state,
+			&precheck,
 			cell_get_transform(point, state),
 			commands,
 			mma,
@@ -156,12 +170,16 @@ fn despawn_markers(
 fn spawn_mark(
 	at: ChessPoint,
 	state: &BorrowedMarkersState,
+	precheck: &Precheck,
 	cell_transform: Transform,
 
 	commands: &mut Commands,
 	(meshes, materials, ass): &mut ResSpawning,
 ) {
-	if let Some(mark) = compute::get_cached_mark(&state.clone_into_compute_with_start(at)) {
+	// cells the precheck rules out are marked failed without waiting for a computation
+	let mark = compute::get_cached_mark(&state.clone_into_compute_with_start(at))
+		.or_else(|| precheck.impossible_from(at).map(|_| CellMark::Failed));
+	if let Some(mark) = mark {
 		let quad = shape::Quad::new(Vec2::new(CELL_SIZE, CELL_SIZE) * 0.7);
 		let mesh = meshes.add(Mesh::from(quad));
 
@@ -1,7 +1,7 @@
 use super::*;
 use crate::{
 	board::coloured_moves::ColouredMoves,
-	solver::Move,
+	solver::{pieces::ChessPiece, Move},
 	textmesh::{get_text_mesh, Fonts},
 	utils::{EntityCommandsExt, TransformExt},
 	ChessPoint, CELL_SIZE, VISUALIZATION_HEIGHT,
@@ -67,6 +67,7 @@ impl SharedState {
 				spawn_visualization(
 					state.moves.clone(),
 					state.board_options.clone(),
+					&(&state.piece).into(),
 					&state.visual_opts,
 					&mut commands,
 					&mut mma,
@@ -88,6 +89,7 @@ mod vis_state {
 	pub struct OwnedVisState {
 		pub moves: Option<ColouredMoves>,
 		pub board_options: BoardOptions,
+		pub piece: Piece,
 		pub visual_opts: VisualOpts,
 	}
 
@@ -96,6 +98,7 @@ mod vis_state {
 			Self {
 				moves: state.moves.clone(),
 				board_options: state.board_options.clone(),
+				piece: state.piece.clone(),
 				visual_opts: state.visual_opts.clone(),
 			}
 		}
@@ -106,6 +109,7 @@ mod vis_state {
 fn spawn_visualization(
 	moves: Option<ColouredMoves>,
 	options: BoardOptions,
+	piece: &ChessPiece,
 	viz_options: &VisualOpts,
 
 	commands: &mut Commands,
@@ -121,6 +125,7 @@ fn spawn_visualization(
 					number: i,
 				},
 				&options,
+				piece,
 				viz_options,
 				commands,
 				mma,
@@ -135,6 +140,7 @@ fn spawn_visualization(
 				number: i,
 			},
 			&options,
+			piece,
 			viz_options,
 			commands,
 			mma,
@@ -151,9 +157,54 @@ fn despawn_visualization(
 	}
 }
 
+/// Where to draw a move, as one line or, for moves that wrap around a joined edge,
+/// one line out of the edge and one back in through the opposite edge
+fn move_segments(
+	options: &BoardOptions,
+	piece: &ChessPiece,
+	from: ChessPoint,
+	to: ChessPoint,
+) -> Vec<(Vec2, Vec2)> {
+	let start_pos = get_spacial_coord_2d(options, from);
+	let end_pos = get_spacial_coord_2d(options, to);
+
+	// moves that can also be made without wrapping are drawn straight
+	let Some(((dx, dy), seam)) = options.wrapped_move(piece, from, to) else {
+		return vec![(start_pos, end_pos)];
+	};
+
+	let from_raw = (from.row as i32, from.column as i32);
+	let exit =
+		get_spacial_coord_2d_unbounded(options, (from_raw.0 + dx as i32, from_raw.1 + dy as i32));
+	let entry = get_spacial_coord_2d_unbounded(options, seam.apply(from_raw, options.height()));
+	let half_size = Vec2::new(options.width() as f32, options.height() as f32) * CELL_SIZE / 2.;
+	vec![
+		(start_pos, clip_to_board(start_pos, exit, half_size)),
+		(clip_to_board(end_pos, entry, half_size), end_pos),
+	]
+}
+
+/// Where the line from `inside` to `outside` crosses the edge of the board
+fn clip_to_board(inside: Vec2, outside: Vec2, half_size: Vec2) -> Vec2 {
+	let delta = outside - inside;
+	let mut t: f32 = 1.;
+	for (pos, delta, half) in [
+		(inside.x, delta.x, half_size.x),
+		(inside.y, delta.y, half_size.y),
+	] {
+		if delta > 0. {
+			t = t.min((half - pos) / delta);
+		} else if delta < 0. {
+			t = t.min((-half - pos) / delta);
+		}
+	}
+	inside + delta * t
+}
+
 fn spawn_path_line(
 	vis: VisComponent,
 	options: &BoardOptions,
+	piece: &ChessPiece,
 	viz_options: &VisualOpts,
 
 	commands: &mut Commands,
@@ -169,34 +220,7 @@ fn spawn_path_line(
 	let to = *vis.to();
 
 	let start_pos = get_spacial_coord_2d(options, from);
-	let end_pos = get_spacial_coord_2d(options, to);
 	let start_vec = &Vec3::new(start_pos.x, VISUALIZATION_HEIGHT * 1.1, start_pos.y);
-	let _end_vec = Vec3::new(end_pos.x, VISUALIZATION_HEIGHT * 1.1, end_pos.y);
-
-	let center = (start_pos + end_pos) / 2.; // ✅
-	let mut length = (start_pos - end_pos).length(); // ✅
-	if matches!(vis, VisComponent::RecommendedMove { .. }) {
-		length *= 0.7;
-	}
-	let angle: f32 = -(start_pos.y - end_pos.y).atan2(start_pos.x - end_pos.x);
-
-	// assert_eq!(angle, TAU / 8., "Drawing from {from} [{from:?}] [{from_pos}] to {to} [{to:?}] [{to_pos}], Angle: {angle}, 𝚫y: {}, 𝚫x: {}", (to_pos.y - from_pos.y), (to_pos.x - from_pos.x));
-	// info!("Angle: {angle}, {}", angle.to_degrees());
-
-	let center = &Vec3::new(center.x, VISUALIZATION_HEIGHT, center.y);
-	let transform = Transform::from_translation(*center).with_rotation(Quat::from_rotation_y(angle));
-
-	// info!("Transform: {:?}", transform);
-	// info!("Angle: {:?}, Length: {:?}", angle, length);
-
-	let mesh_thin_rectangle = meshs.add(
-		shape::Box::new(
-			length,
-			viz_options.dimensions().x,
-			viz_options.dimensions().y,
-		)
-		.into(),
-	);
 
 	let material = match vis {
 		VisComponent::Move { colour, number, .. } => mat.add({
@@ -210,15 +234,56 @@ fn spawn_path_line(
 			mat
 		}),
 	};
-	commands.spawn((
-		PbrBundle {
-			mesh: mesh_thin_rectangle,
-			material: material.clone(),
-			transform,
-			..default()
-		},
-		vis,
-	));
+
+	let mut angle: f32 = 0.;
+	let segments = move_segments(options, piece, from, to);
+	let last = segments.len() - 1;
+	for (i, (mut start_pos, mut end_pos)) in segments.into_iter().enumerate() {
+		// shortened at the cells only, so both parts of a wrapped move still meet the board edge
+		if matches!(vis, VisComponent::RecommendedMove { .. }) {
+			let delta = end_pos - start_pos;
+			if i == 0 {
+				start_pos += delta * 0.15;
+			}
+			if i == last {
+				end_pos -= delta * 0.15;
+			}
+		}
+		let center = (start_pos + end_pos) / 2.; // ✅
+		let length = (start_pos - end_pos).length(); // ✅
+		let segment_angle = -(start_pos.y - end_pos.y).atan2(start_pos.x - end_pos.x);
+		if i == 0 {
+			angle = segment_angle;
+		}
+
+		// assert_eq!(angle, TAU / 8., "Drawing from {from} [{from:?}] [{from_pos}] to {to} [{to:?}] [{to_pos}], Angle: {angle}, 𝚫y: {}, 𝚫x: {}", (to_pos.y - from_pos.y), (to_pos.x - from_pos.x));
+		// info!("Angle: {angle}, {}", angle.to_degrees());
+
+		let center = &Vec3::new(center.x, VISUALIZATION_HEIGHT, center.y);
+		let transform =
+			Transform::from_translation(*center).with_rotation(Quat::from_rotation_y(segment_angle));
+
+		// info!("Transform: {:?}", transform);
+		// info!("Angle: {:?}, Length: {:?}", angle, length);
+
+		let mesh_thin_rectangle = meshs.add(
+			shape::Box::new(
+				length,
+				viz_options.dimensions().x,
+				viz_options.dimensions().y,
+			)
+			.into(),
+		);
+		commands.spawn((
+			PbrBundle {
+				mesh: mesh_thin_rectangle,
+				material: material.clone(),
+				transform,
+				..default()
+			},
+			vis,
+		));
+	}
 
 	// small dot at start
 	if viz_options.show_dots && matches!(vis, VisComponent::Move { .. }) {
@@ -1,6 +1,6 @@
 use crate::ProgramState;
 
-use super::*;
+use super::{automatic::SolutionBrowser, compute::ComputeManager, *};
 
 use bevy_egui::{egui::*, *};
 
@@ -28,6 +28,7 @@ pub fn left_ui(mut contexts: EguiContexts, state: ResMut<SharedState>) {
 				ui.label(state.alg.get_description());
 
 				state.safety_cap.ui(ui);
+				state.pruning.ui(ui);
 			});
 
 		ui.collapsing("Cell Colouring", |ui| {
@@ -37,7 +38,8 @@ pub fn left_ui(mut contexts: EguiContexts, state: ResMut<SharedState>) {
 		egui::CollapsingHeader::new("Change board")
 			.default_open(true)
 			.show(ui, |ui| {
-				state.board_options.ui(ui).invalidates(state);
+				let max_size = state.alg.max_board_size();
+				state.board_options.ui(ui, max_size).invalidates(state);
 				if let Some(cell) = state.last_clicked_recommended_move {
 					ui.label(format!(
 						"Currently waiting for the second recommended move starting on cell: {:?}",
@@ -46,16 +48,33 @@ pub fn left_ui(mut contexts: EguiContexts, state: ResMut<SharedState>) {
 				}
 			});
 
+		ui.collapsing("Board layout text", |ui| {
+			state.layout_ui(ui);
+		});
+
 		ui.collapsing("Visualisation options", |ui| {
 			state.visual_opts.ui(ui);
 			state.cam_zoom.ui(ui);
 		});
 
+		#[cfg(not(target_arch = "wasm32"))]
+		ui.collapsing("Export", |ui| {
+			state.export_ui(ui);
+		});
+
 		ui.collapsing("Non-standard pieces", |ui| {
 			ui.label("Set a piece that is not a standard knight");
 			state.piece.ui(ui).invalidates(state);
 		});
 
+		ui.collapsing("Piece editor", |ui| {
+			ui.label("Click cells around the piece to choose where it can jump to");
+			state
+				.piece_editor
+				.ui(ui, &mut state.piece)
+				.invalidates(state);
+		});
+
 		ui.collapsing("About", |ui| {
 			ui.label(
 				"This project was created to advance the knowledge of humanity in the field of chess, \
@@ -77,6 +96,8 @@ pub fn left_ui(mut contexts: EguiContexts, state: ResMut<SharedState>) {
 pub fn right_ui_automatic(
 	mut contexts: EguiContexts,
 	state: ResMut<SharedState>,
+	mut manager: ResMut<ComputeManager>,
+	mut browser: ResMut<SolutionBrowser>,
 	mut to_manual: ResMut<NextState<ProgramState>>,
 ) {
 	egui::SidePanel::right("Right sidebar (automatic)").show(contexts.ctx_mut(), |ui| {
@@ -95,7 +116,15 @@ pub fn right_ui_automatic(
 			});
 
 		ui.collapsing("Results summary", |ui| {
-			state.summarize(ui);
+			state.summarize(ui, &mut manager);
+		});
+
+		ui.collapsing("All solutions", |ui| {
+			browser.ui(ui, state.clone().get_compute_state());
+		});
+
+		ui.collapsing("Share", |ui| {
+			state.share_link_ui(ui);
 		});
 	});
 }
@@ -161,6 +190,18 @@ pub fn right_ui_manual(
 				.show(ui, |ui| {
 					state.save_ui(ui);
 				});
+
+			egui::CollapsingHeader::new("Share")
+				.default_open(true)
+				.show(ui, |ui| {
+					state.share_link_ui(ui);
+				});
+
+			egui::CollapsingHeader::new("Notation")
+				.default_open(true)
+				.show(ui, |ui| {
+					state.notation_ui(ui);
+				});
 		});
 	});
 }
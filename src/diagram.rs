@@ -0,0 +1,364 @@
+//! Standalone SVG drawings of a board and its tour, for reports and slides.
+//! With the `png` feature, [render_png] rasterises them in software, so they can also be made headless from `cap-cli`
+
+use std::{collections::HashMap, fmt::Write};
+
+#[cfg(feature = "png")]
+use anyhow::Context;
+#[cfg(feature = "png")]
+use resvg::{
+	tiny_skia,
+	usvg::{self, TreeParsing, TreePostProc},
+};
+
+use crate::solver::{
+	notation::square_name, pieces::ChessPiece, BoardOptions, CellOption, ChessPoint, Move,
+};
+
+/// Red, green, blue and alpha between 0 and 1, like `bevy::prelude::Color::as_rgba_f32`
+pub type Rgba = [f32; 4];
+
+pub const WHITE: Rgba = [1., 1., 1., 1.];
+pub const BLACK: Rgba = [0., 0., 0., 1.];
+/// Same as the default `VizColour` of the GUI
+pub const GREEN: Rgba = [0., 1., 0., 1.];
+const RED: Rgba = [1., 0., 0., 1.];
+const PURPLE: Rgba = [0.5, 0., 0.5, 1.];
+const RECOMMENDED: Rgba = [1., 1., 0., 0.7];
+
+/// Pixels per cell, before scaling
+const CELL: f32 = 60.;
+/// Room around the board for the row and column names
+const MARGIN: f32 = 24.;
+
+/// Everything drawn, see [Diagram::new] for the defaults
+#[derive(Debug, Clone)]
+pub struct Diagram {
+	pub board_options: BoardOptions,
+	/// Used to draw moves that wrap around joined edges
+	pub piece: ChessPiece,
+	/// Colour of every cell
+	pub cell_colours: HashMap<ChessPoint, Rgba>,
+	/// Numbered in order, like on the 3D board
+	pub moves: Vec<(Move, Rgba)>,
+	pub show_numbers: bool,
+}
+
+impl Diagram {
+	/// A black and white board with no moves,
+	/// unavailable cells in red and `start` in purple like the standard cell colouring
+	pub fn new(board_options: BoardOptions, piece: ChessPiece, start: Option<ChessPoint>) -> Self {
+		let cell_colours = board_options
+			.get_all_points()
+			.into_iter()
+			.map(|point| {
+				let colour = if !board_options.is_available(&point) {
+					RED
+				} else if Some(point) == start {
+					PURPLE
+				} else if point.is_white() {
+					WHITE
+				} else {
+					BLACK
+				};
+				(point, colour)
+			})
+			.collect();
+		Self {
+			board_options,
+			piece,
+			cell_colours,
+			moves: Vec::new(),
+			show_numbers: true,
+		}
+	}
+
+	fn width(&self) -> f32 {
+		self.board_options.width() as f32 * CELL + 2. * MARGIN
+	}
+
+	fn height(&self) -> f32 {
+		self.board_options.height() as f32 * CELL + 2. * MARGIN
+	}
+
+	/// Centre of a `(row, column)` position, which can be off the board
+	fn centre(&self, (row, column): (i32, i32)) -> (f32, f32) {
+		let height = self.board_options.height() as f32;
+		(
+			MARGIN + (column as f32 - 0.5) * CELL,
+			MARGIN + (height - row as f32 + 0.5) * CELL,
+		)
+	}
+
+	fn point_centre(&self, point: ChessPoint) -> (f32, f32) {
+		self.centre((point.row as i32, point.column as i32))
+	}
+
+	/// Where the line from `inside` to `outside` crosses the edge of the board
+	fn clip_to_board(&self, inside: (f32, f32), outside: (f32, f32)) -> (f32, f32) {
+		let delta = (outside.0 - inside.0, outside.1 - inside.1);
+		let mut t: f32 = 1.;
+		for (pos, delta, end) in [
+			(inside.0, delta.0, self.width() - MARGIN),
+			(inside.1, delta.1, self.height() - MARGIN),
+		] {
+			if delta > 0. {
+				t = t.min((end - pos) / delta);
+			} else if delta < 0. {
+				t = t.min((MARGIN - pos) / delta);
+			}
+		}
+		(inside.0 + delta.0 * t, inside.1 + delta.1 * t)
+	}
+
+	/// Like the 3D board, moves around a joined edge are drawn out of one side and back in the other
+	fn segments(&self, Move { from, to }: Move) -> Vec<((f32, f32), (f32, f32))> {
+		let (start, end) = (self.point_centre(from), self.point_centre(to));
+		let Some(((dx, dy), seam)) = self.board_options.wrapped_move(&self.piece, from, to) else {
+			return vec![(start, end)];
+		};
+		let from_raw = (from.row as i32, from.column as i32);
+		let exit = self.centre((from_raw.0 + dx as i32, from_raw.1 + dy as i32));
+		let entry = self.centre(seam.apply(from_raw, self.board_options.height()));
+		vec![
+			(start, self.clip_to_board(start, exit)),
+			(self.clip_to_board(end, entry), end),
+		]
+	}
+
+	pub fn to_svg(&self) -> String {
+		let mut svg = String::new();
+		let (width, height) = (self.width(), self.height());
+		writeln!(
+			svg,
+			r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+			w = width,
+			h = height
+		)
+		.unwrap();
+		writeln!(
+			svg,
+			r#"<rect width="{}" height="{}" fill="white"/>"#,
+			width, height
+		)
+		.unwrap();
+
+		let options = &self.board_options;
+		for point in options.get_all_points() {
+			let (x, y) = self.point_centre(point);
+			let colour = self.cell_colours.get(&point).copied().unwrap_or(WHITE);
+			writeln!(
+				svg,
+				r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+				x - CELL / 2.,
+				y - CELL / 2.,
+				CELL,
+				CELL,
+				paint("fill", colour)
+			)
+			.unwrap();
+		}
+		writeln!(
+			svg,
+			r#"<rect x="{m}" y="{m}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+			width - 2. * MARGIN,
+			height - 2. * MARGIN,
+			m = MARGIN
+		)
+		.unwrap();
+
+		// names like a1, along the bottom and left
+		for column in 1..=options.width() {
+			let (x, _) = self.point_centre(ChessPoint::new(1, column));
+			let name = square_name(ChessPoint::new(1, column));
+			writeln!(
+				svg,
+				r#"<text x="{}" y="{}" font-size="14" text-anchor="middle">{}</text>"#,
+				x,
+				height - MARGIN / 3.,
+				name.trim_end_matches(|c: char| c.is_ascii_digit())
+			)
+			.unwrap();
+		}
+		for row in 1..=options.height() {
+			let (_, y) = self.point_centre(ChessPoint::new(row, 1));
+			writeln!(
+				svg,
+				r#"<text x="{}" y="{}" font-size="14" text-anchor="middle">{}</text>"#,
+				MARGIN / 2.,
+				y + 5.,
+				row
+			)
+			.unwrap();
+		}
+
+		if options.targets_state().should_show_targets_visual() {
+			for point in options.get_available_points() {
+				if options.get(&point)
+					== Some(CellOption::Available {
+						can_finish_on: true,
+					}) {
+					let (x, y) = self.point_centre(point);
+					writeln!(
+						svg,
+						r#"<circle cx="{x}" cy="{y}" r="{}" fill="none" stroke="red" stroke-width="{}"/><circle cx="{x}" cy="{y}" r="{}" fill="red"/>"#,
+						CELL * 0.35,
+						CELL * 0.06,
+						CELL * 0.12,
+						x = x,
+						y = y
+					)
+					.unwrap();
+				}
+			}
+		}
+
+		for (m, colour) in self.moves.iter() {
+			if colour[3] == 0. {
+				continue;
+			}
+			self.write_line(&mut svg, *m, *colour, 1.);
+			let (x, y) = self.point_centre(m.from);
+			writeln!(
+				svg,
+				r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+				x,
+				y,
+				CELL / 12.,
+				paint("fill", *colour)
+			)
+			.unwrap();
+		}
+		for m in options.recommended_moves().iter() {
+			self.write_line(&mut svg, *m, RECOMMENDED, 0.7);
+		}
+
+		if self.show_numbers {
+			for (number, (m, colour)) in self.moves.iter().enumerate() {
+				if colour[3] == 0. {
+					continue;
+				}
+				let (x, y) = self.point_centre(m.from);
+				writeln!(
+					svg,
+					r#"<text x="{}" y="{}" font-size="{}" fill="red" stroke="white" stroke-width="0.5">{}</text>"#,
+					x + CELL / 8.,
+					y - CELL / 8.,
+					CELL / 3.,
+					number
+				)
+				.unwrap();
+			}
+		}
+
+		svg.push_str("</svg>\n");
+		svg
+	}
+
+	/// `length` is the fraction of the line drawn, cut off at the cells so both parts of a wrapped move
+	/// still meet the edge of the board
+	fn write_line(&self, svg: &mut String, m: Move, colour: Rgba, length: f32) {
+		let shrink = (1. - length) / 2.;
+		let segments = self.segments(m);
+		let last = segments.len() - 1;
+		for (i, (start, end)) in segments.into_iter().enumerate() {
+			let delta = (end.0 - start.0, end.1 - start.1);
+			let start_shrink = if i == 0 { shrink } else { 0. };
+			let end_shrink = if i == last { shrink } else { 0. };
+			writeln!(
+				svg,
+				r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke-width="{}" stroke-linecap="round" {}/>"#,
+				start.0 + delta.0 * start_shrink,
+				start.1 + delta.1 * start_shrink,
+				end.0 - delta.0 * end_shrink,
+				end.1 - delta.1 * end_shrink,
+				CELL / 15.,
+				paint("stroke", colour)
+			)
+			.unwrap();
+		}
+	}
+}
+
+/// `fill="rgb(..)"` or `stroke="rgb(..)"`, with an opacity if it isn't opaque
+fn paint(attribute: &str, [r, g, b, a]: Rgba) -> String {
+	let channel = |c: f32| (c.clamp(0., 1.) * 255.).round() as u8;
+	let mut paint = format!(
+		r#"{}="rgb({},{},{})""#,
+		attribute,
+		channel(r),
+		channel(g),
+		channel(b)
+	);
+	if a < 1. {
+		write!(paint, r#" {}-opacity="{}""#, attribute, a).unwrap();
+	}
+	paint
+}
+
+/// Rasterises an SVG made by [Diagram::to_svg], `scale` times as large.
+/// Text uses the fonts installed on the system, and is left out if there are none
+#[cfg(feature = "png")]
+pub fn render_png(svg: &str, scale: f32) -> Result<Vec<u8>, anyhow::Error> {
+	let mut tree =
+		usvg::Tree::from_str(svg, &usvg::Options::default()).context("Failed to parse the SVG")?;
+	let mut fonts = usvg::fontdb::Database::new();
+	fonts.load_system_fonts();
+	tree.postprocess(
+		usvg::PostProcessingSteps {
+			convert_text_into_paths: true,
+		},
+		&fonts,
+	);
+
+	let size = tree
+		.size
+		.to_int_size()
+		.scale_by(scale)
+		.context("Bad scale")?;
+	let mut pixmap =
+		tiny_skia::Pixmap::new(size.width(), size.height()).context("The image is too large")?;
+	resvg::render(
+		&tree,
+		tiny_skia::Transform::from_scale(scale, scale),
+		&mut pixmap.as_mut(),
+	);
+	pixmap.encode_png().context("Failed to encode the PNG")
+}
+
+#[test]
+fn draws_boards_and_tours() {
+	use crate::solver::{pieces::StandardPieces, Topology};
+
+	let mut options = BoardOptions::new(5, 5);
+	options.set_point((3, 3), CellOption::Unavailable);
+	options.toggle_target((5, 5));
+	options.set_topology(Topology::Cylinder);
+	let knight: ChessPiece = StandardPieces::StandardKnight.into();
+	let mut diagram = Diagram::new(options, knight, Some(ChessPoint::new(1, 1)));
+	assert_eq!(diagram.cell_colours[&ChessPoint::new(3, 3)], RED);
+	assert_eq!(diagram.cell_colours[&ChessPoint::new(1, 1)], PURPLE);
+
+	let straight = Move::new(ChessPoint::new(1, 1), ChessPoint::new(2, 3));
+	// off the right edge, back on the left
+	let wrapped = Move::new(ChessPoint::new(1, 5), ChessPoint::new(2, 2));
+	diagram.moves = vec![(straight, GREEN), (wrapped, [0., 0., 1., 0.5])];
+	assert_eq!(diagram.segments(straight).len(), 1);
+	assert_eq!(diagram.segments(wrapped).len(), 2);
+
+	let svg = diagram.to_svg();
+	assert!(svg.starts_with("<svg"));
+	assert_eq!(svg.matches("<line").count(), 3);
+	assert!(svg.contains(r#"stroke="rgb(0,0,255)" stroke-opacity="0.5""#));
+	assert!(svg.contains(">e</text>"));
+	// the target
+	assert!(svg.contains(r#"fill="red"/>"#));
+
+	#[cfg(feature = "png")]
+	{
+		let png = render_png(&svg, 2.).unwrap();
+		assert_eq!(&png[1..4], b"PNG");
+		let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+		assert_eq!(width as f32, diagram.width() * 2.);
+	}
+}
@@ -1,7 +1,13 @@
 
 
+//! The solver lives in [solver] and has no GUI dependencies.
+//! Everything else is the Bevy app, behind the `gui` feature.
+
+#[cfg(feature = "gui")]
 use bevy::prelude::*;
+#[cfg(feature = "gui")]
 use bevy_egui::{EguiPlugin};
+#[cfg(feature = "gui")]
 use bevy_mod_picking::{
 	prelude::{
 		Click, IsPointerEvent, ListenedEvent, OnPointer, RaycastPickCamera, RaycastPickTarget,
@@ -9,20 +15,29 @@ use bevy_mod_picking::{
 	PickableBundle,
 };
 
+#[cfg(feature = "gui")]
 mod board;
 pub mod solver;
 
+#[cfg(feature = "gui")]
 use board::BoardPlugin;
 pub use solver::ChessPoint;
+#[cfg(feature = "gui")]
 mod clipboard;
+#[cfg(feature = "gui")]
 mod errors;
+#[cfg(feature = "gui")]
 mod textmesh;
+#[cfg(feature = "gui")]
 mod utils;
+pub mod diagram;
 pub mod meta;
 pub mod weburl;
 
+#[cfg(feature = "gui")]
 #[derive(Default)]
 pub struct ChessSolverPlugin;
+#[cfg(feature = "gui")]
 impl Plugin for ChessSolverPlugin {
 	fn build(&self, app: &mut App) {
 		app
@@ -34,6 +49,7 @@ impl Plugin for ChessSolverPlugin {
 	}
 }
 
+#[cfg(feature = "gui")]
 #[derive(
 	States, derive_more::Display, strum::EnumIs, Default, Clone, Copy, PartialEq, Eq, Debug, Hash,
 )]
@@ -44,30 +60,40 @@ pub enum ProgramState {
 	Manual,
 }
 
+#[cfg(feature = "gui")]
 use board::CAMERA_HEIGHT;
+#[cfg(feature = "gui")]
 const LIGHT_HEIGHT: f32 = CAMERA_HEIGHT;
 
 /// Square width and height
+#[cfg(feature = "gui")]
 const CELL_SIZE: f32 = 5.;
 /// Distance from ground plane, y = 0
+#[cfg(feature = "gui")]
 const CELL_HEIGHT: f32 = 1.;
 /// Depth of cell
+#[cfg(feature = "gui")]
 const CELL_DEPTH: f32 = 2.;
 
+#[cfg(feature = "gui")]
 const VISUALIZATION_HEIGHT: f32 = 3.;
 
+#[cfg(feature = "gui")]
 #[derive(Component)]
 pub struct MainCamera;
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 pub struct GroundClicked;
 
+#[cfg(feature = "gui")]
 impl<T: IsPointerEvent> From<ListenedEvent<T>> for GroundClicked {
 	fn from(_: ListenedEvent<T>) -> Self {
 		GroundClicked
 	}
 }
 
+#[cfg(feature = "gui")]
 pub fn setup(
 	mut commands: Commands,
 	mut meshes: ResMut<Assets<Mesh>>,
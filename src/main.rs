@@ -3,33 +3,36 @@ use cap_solver::*;
 
 fn main() {
 	#[cfg(not(target_arch = "wasm32"))]
-	main2(None);
+	main2(None, None);
 
 	#[cfg(target_arch = "wasm32")]
 	{
-		if let Some(id) = weburl::get_url_id() {
-			info!("Loaded id from URL: {:?}", id);
-
-			let url =
-				format!(
-			"https://chess-analysis-program-default-rtdb.asia-southeast1.firebasedatabase.app/{}/{}.json", *meta::VERSION_APPEND, id);
-
-			wasm_bindgen_futures::spawn_local(async {
-				if let Ok(data) = reqwest::get(url).await {
-					if let Ok(data) = data.json().await {
-						main2(Some(data));
-						return;
+		match weburl::get_url_data() {
+			Some(weburl::UrlData::Id(id)) => {
+				info!("Loaded id from URL: {:?}", id);
+
+				let url =
+					format!(
+				"https://chess-analysis-program-default-rtdb.asia-southeast1.firebasedatabase.app/{}/{}.json", *meta::VERSION_APPEND, id);
+
+				wasm_bindgen_futures::spawn_local(async {
+					if let Ok(data) = reqwest::get(url).await {
+						if let Ok(data) = data.json().await {
+							main2(Some(data), None);
+							return;
+						}
 					}
-				}
-				main2(None);
-			});
-		} else {
-			main2(None);
+					main2(None, None);
+				});
+			}
+			// everything is in the link, no need to wait for the database
+			Some(weburl::UrlData::Board(board)) => main2(None, Some(board)),
+			None => main2(None, None),
 		}
 	}
 }
 
-fn main2(data: Option<serde_json::Value>) {
+fn main2(data: Option<serde_json::Value>, board: Option<weburl::SharedBoard>) {
 	let mut app = App::new();
 
 	app
@@ -54,6 +57,9 @@ fn main2(data: Option<serde_json::Value>) {
 	if let Some(data) = data {
 		app.insert_resource(weburl::InitialLoadedID::new(data));
 	}
+	if let Some(board) = board {
+		app.insert_resource(board);
+	}
 
 	app.run();
 }
@@ -1,32 +1,81 @@
-use crate::{
-	board::Hotkeyable,
-	solver::{pieces::ChessPiece, *},
-};
-use bevy_egui_controls::ControlPanel;
-use strum::{Display, EnumIter, IntoStaticStr};
+use std::time::Duration;
+
+use crate::solver::{pieces::ChessPiece, *};
+use tracing::{debug, error};
 
 mod hamiltonian;
 use hamiltonian::hamiltonian_tour_repeatless;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub use registry::{register_solver, solvers, Algorithm, TourSolver};
+mod registry;
+
+pub use handle::ComputeHandle;
+mod handle;
+
+pub use limits::{GiveUpReason, Limits};
+mod limits;
+
+use pruning::PruneCounter;
+pub use pruning::{PruneCounts, PruningRules};
+mod pruning;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use enumerate::tours;
+pub use enumerate::{count_tours, enumerate_tours, Enumeration, TourKind};
+mod enumerate;
+
+pub use construct::{construct_closed_tour, MAX_CONSTRUCTED_SIZE};
+mod construct;
+
+pub use precheck::{precheck, Colour, Impossible, Precheck};
+mod precheck;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Computation {
 	Successful {
 		solution: Moves,
 		explored_states: u128,
+		#[serde(default)]
+		pruned: PruneCounts,
 	},
 	Failed {
 		total_states: u128,
+		#[serde(default)]
+		pruned: PruneCounts,
+		/// Set when [precheck] proved there is no tour, without searching
+		#[serde(default)]
+		impossible: Option<Impossible>,
 	},
 
 	GivenUp {
 		explored_states: u128,
+		reason: GiveUpReason,
+		/// How long the search ran before giving up
+		elapsed: Duration,
+		#[serde(default)]
+		pruned: PruneCounts,
 	},
 }
 
+impl Computation {
+	pub fn pruned(&self) -> PruneCounts {
+		match self {
+			Self::Successful { pruned, .. }
+			| Self::Failed { pruned, .. }
+			| Self::GivenUp { pruned, .. } => *pruned,
+		}
+	}
+}
+
 #[derive(Hash, PartialEq, Eq, Clone)]
 pub struct OwnedComputeInput {
 	pub alg: Algorithm,
-	pub safety_cap: u128,
+	/// Maximum number of states explored before giving up
+	pub safety_cap: Option<u128>,
+	/// Maximum wall-clock time spent before giving up
+	pub time_budget: Option<Duration>,
+	/// Only used by the brute force solvers
+	pub pruning: PruningRules,
 	pub start: ChessPoint,
 	pub board_options: BoardOptions,
 	pub piece: ChessPiece,
@@ -37,24 +86,30 @@ pub enum PartialComputation {
 	Successful { solution: Moves },
 	Failed,
 
-	GivenUp,
+	GivenUp(GiveUpReason),
 }
 
 mod parital_computation {
 	use super::*;
 
 	impl PartialComputation {
-		pub fn add_state_count(self, count: u128) -> Computation {
+		pub fn add_state_count(self, count: u128, pruned: PruneCounts, limits: &Limits) -> Computation {
 			match self {
 				Self::Successful { solution } => Computation::Successful {
 					solution,
 					explored_states: count,
+					pruned,
 				},
 				Self::Failed => Computation::Failed {
 					total_states: count,
+					pruned,
+					impossible: None,
 				},
-				Self::GivenUp => Computation::GivenUp {
+				Self::GivenUp(reason) => Computation::GivenUp {
 					explored_states: count,
+					reason,
+					elapsed: limits.elapsed(),
+					pruned,
 				},
 			}
 		}
@@ -65,7 +120,7 @@ mod parital_computation {
 					solution: f(solution),
 				},
 				Self::Failed => Self::Failed,
-				Self::GivenUp => Self::GivenUp,
+				Self::GivenUp(reason) => Self::GivenUp(reason),
 			}
 		}
 	}
@@ -80,114 +135,254 @@ mod parital_computation {
 	}
 }
 
-#[derive(
-	Copy,
-	Debug,
-	Clone,
-	Default,
-	PartialEq,
-	Eq,
-	EnumIter,
-	IntoStaticStr,
-	Hash,
-	PartialOrd,
-	Ord,
-	Display,
-	ControlPanel,
-	Reflect,
-	FromReflect,
-)]
-pub enum Algorithm {
-	#[strum(serialize = "Brute Force [f]")]
-	#[default]
-	BruteForceWarnsford,
-
-	#[strum(serialize = "Warnsdorf (incomplete) [w]")]
-	WarnsdorfBacktrack,
-
-	#[strum(serialize = "Hamiltonian Cycle [c]")]
-	HamiltonianCycle,
-
-	#[strum(serialize = "Hamiltonian Cycle Brute Force [h]")]
-	HamiltonianBruteForce,
-}
+struct BruteForceWarnsford;
+impl TourSolver for BruteForceWarnsford {
+	fn id(&self) -> &'static str {
+		"brute-force"
+	}
 
-impl From<Algorithm> for KeyCode {
-	fn from(value: Algorithm) -> Self {
-		match value {
-			Algorithm::BruteForceWarnsford => KeyCode::F,
-			Algorithm::WarnsdorfBacktrack => KeyCode::W,
-			Algorithm::HamiltonianCycle => KeyCode::C,
-			Algorithm::HamiltonianBruteForce => KeyCode::H,
-		}
+	fn name(&self) -> &'static str {
+		"Brute Force"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"COMPLETE open knights tour. Takes into account targets and recommended moves.
+This algorithm is a Warnsdorf-biased brute force, which checks every possible path a knight can take (without repeating squares). \
+			It contains no heuristics for targets, and will finish after the first valid path is found. \
+			This algorithm works best with small boards and with a high saftey-states cap (preferrably not on web).
+			"
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('f')
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+		let limits = Limits::from(&input);
+		brute_recursive_repeatless(
+			&input.piece,
+			input.board_options,
+			input.start,
+			TourType::BruteForceOpen,
+			limits,
+			input.pruning,
+			handle,
+			None,
+		)
 	}
 }
-impl Hotkeyable for Algorithm {}
 
-impl Algorithm {
-	pub fn get_description(&self) -> &'static str {
-		match self {
-			Algorithm::WarnsdorfBacktrack => "INCOMPLETE open knights tour. Takes into account targets and recommended moves.
+struct WarnsdorfBacktrack;
+impl TourSolver for WarnsdorfBacktrack {
+	fn id(&self) -> &'static str {
+		"warnsdorf"
+	}
+
+	fn name(&self) -> &'static str {
+		"Warnsdorf (incomplete)"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"INCOMPLETE open knights tour. Takes into account targets and recommended moves.
 This algorithm fully implemented Warnsdorf's rule, in that it tries all equal possibilities by backtracking. \
 			As such it is not complete, it won't find a solution to every board (but never finds a false solution). \
 			This algorithm works best with no targets.
-			",
-			Algorithm::BruteForceWarnsford => "COMPLETE open knights tour. Takes into account targets and recommended moves.
-This algorithm is a Warnsdorf-biased brute force, which checks every possible path a knight can take (without repeating squares). \
-			It contains no heuristics for targets, and will finish after the first valid path is found. \
-			This algorithm works best with small boards and with a high saftey-states cap (preferrably not on web).
-			",
-			Algorithm::HamiltonianCycle => "UNTESTED closed knights tour, IGNORES targets and recommended moves.
+			"
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('w')
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+		let limits = Limits::from(&input);
+		brute_recursive_repeatless(
+			&input.piece,
+			input.board_options,
+			input.start,
+			TourType::WeakOpen,
+			limits,
+			input.pruning,
+			handle,
+			None,
+		)
+	}
+}
+
+struct HamiltonianCycle;
+impl TourSolver for HamiltonianCycle {
+	fn id(&self) -> &'static str {
+		"hamiltonian-cycle"
+	}
+
+	fn name(&self) -> &'static str {
+		"Hamiltonian Cycle"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"UNTESTED closed knights tour, IGNORES targets and recommended moves.
 This algorithm tries to find a hamiltonian cycle using a copy-pasted algorithm from the internet. \
 			It appears to work but I haven't audited the code. It will stop if it has surpassed the saftey states cap. \
 			This algorithm is not recommended for use, but is added because I am lazy.
-			",
-			Algorithm::HamiltonianBruteForce => "COMPLETE CLOSED knights tour. Takes into account recommended moves, targets don't make sense so are ignored. \
+			"
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('c')
+	}
+
+	fn tour_kind(&self) -> TourKind {
+		TourKind::Closed
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+		let limits = Limits::from(&input);
+		hamiltonian_tour_repeatless(
+			&input.piece,
+			input.board_options,
+			input.start,
+			limits,
+			true,
+			handle,
+		)
+	}
+}
+
+struct HamiltonianPath;
+impl TourSolver for HamiltonianPath {
+	fn id(&self) -> &'static str {
+		"hamiltonian-path"
+	}
+
+	fn name(&self) -> &'static str {
+		"Hamiltonian Path"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"COMPLETE open knights tour. Takes into account targets, IGNORES recommended moves.
+This algorithm is the open path version of the Hamiltonian Cycle algorithm. \
+			It checks every path without Warnsdorf biasing or pruning, so it is mostly useful to compare against the Brute Force algorithm.
+			"
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('p')
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+		let limits = Limits::from(&input);
+		hamiltonian_tour_repeatless(
+			&input.piece,
+			input.board_options,
+			input.start,
+			limits,
+			false,
+			handle,
+		)
+	}
+}
+
+struct HamiltonianBruteForce;
+impl TourSolver for HamiltonianBruteForce {
+	fn id(&self) -> &'static str {
+		"hamiltonian-brute-force"
+	}
+
+	fn name(&self) -> &'static str {
+		"Hamiltonian Cycle Brute Force"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"COMPLETE CLOSED knights tour. Takes into account recommended moves, targets don't make sense so are ignored. \
 This algorithm uses brute force and will check every possible knight path, with biasing towards Warnsdorf's rule and complete backtracking. \
 See the Brute Force algorithm's description for more.
 			"
-		}
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('h')
+	}
+
+	fn tour_kind(&self) -> TourKind {
+		TourKind::Closed
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+		let limits = Limits::from(&input);
+		brute_recursive_repeatless(
+			&input.piece,
+			input.board_options,
+			input.start,
+			TourType::BruteForceClosed,
+			limits,
+			input.pruning,
+			handle,
+			None,
+		)
 	}
 }
 
-impl Algorithm {
-	pub fn tour_computation(&self, input: OwnedComputeInput) -> Computation {
-		match self {
-			// Algorithm::WarnsdorfUnreliable => warnsdorf_tour_repeatless(piece, options, start),
-			Algorithm::WarnsdorfBacktrack => brute_recursive_repeatless(
-				&input.piece,
-				input.board_options,
-				input.start,
-				TourType::WeakOpen,
-				input.safety_cap,
-			),
-			Algorithm::BruteForceWarnsford => brute_recursive_repeatless(
-				&input.piece,
-				input.board_options,
-				input.start,
-				TourType::BruteForceOpen,
-				input.safety_cap,
-			),
-			Algorithm::HamiltonianBruteForce => brute_recursive_repeatless(
-				&input.piece,
-				input.board_options,
-				input.start,
-				TourType::BruteForceClosed,
-				input.safety_cap,
-			),
-			// Algorithm::HamiltonianPath => hamiltonian_tour_repeatless(piece, options, start, false),
-			Algorithm::HamiltonianCycle => hamiltonian_tour_repeatless(
-				&input.piece,
-				input.board_options,
-				input.start,
-				input.safety_cap,
-				true,
-			),
+struct Constructed;
+impl TourSolver for Constructed {
+	fn id(&self) -> &'static str {
+		"constructed"
+	}
+
+	fn name(&self) -> &'static str {
+		"Constructed (large boards)"
+	}
+
+	fn get_description(&self) -> &'static str {
+		"INSTANT closed knights tour on full boards with both sides at least 5, and not both odd. IGNORES targets and recommended moves.
+This algorithm doesn't search, instead it stitches together brute forced tours of small blocks of the board. \
+			As such it works on boards far larger than any search can handle, but only for the standard knight and only with every cell available. \
+			The tours never cross joined edges, so they are also valid on every other topology.
+			"
+	}
+
+	fn hotkey(&self) -> Option<char> {
+		Some('l')
+	}
+
+	fn tour_kind(&self) -> TourKind {
+		TourKind::Closed
+	}
+
+	fn max_board_size(&self) -> u8 {
+		MAX_CONSTRUCTED_SIZE
+	}
+
+	fn tour_computation(&self, input: OwnedComputeInput, _handle: &ComputeHandle) -> Computation {
+		let options = &input.board_options;
+		let (width, height) = (options.width(), options.height());
+		let full = options.get_available_points().len() == width as usize * height as usize;
+		let solution = if full && input.piece == ChessPiece::default() {
+			construct_closed_tour(width, height, input.start)
+		} else {
+			None
+		};
+		match solution {
+			Some(solution) => Computation::Successful {
+				solution,
+				explored_states: 0,
+				pruned: PruneCounts::default(),
+			},
+			None => Computation::Failed {
+				total_states: 0,
+				pruned: PruneCounts::default(),
+				impossible: None,
+			},
 		}
 	}
+}
 
-	/// Actually compute, with caching
-	pub fn tour_computation_cached(input: OwnedComputeInput) -> Option<Computation> {
+impl Algorithm {
+	/// Actually compute, with caching.
+	/// Results of cancelled computations are not cached
+	pub fn tour_computation_cached(
+		input: OwnedComputeInput,
+		handle: &ComputeHandle,
+	) -> Option<Computation> {
 		if !input
 			.board_options
 			.get_available_points()
@@ -197,211 +392,127 @@ impl Algorithm {
 			return None;
 		}
 		if let Some(cached_comp) = try_get_cached_solution(&input) {
+			// the limits are part of the key, so a cached GivenUp is still valid
 			debug!("Solution cache hit!");
-
-			if let Computation::GivenUp { explored_states } = cached_comp {
-				if explored_states != input.safety_cap {
-					// must recompute
-					trace!(
-						"Cache hit but recomputing because {} != {} (old != new)",
-						explored_states,
-						input.safety_cap
-					);
-					let comp = input.alg.tour_computation(input.clone());
-					add_solution_to_cache(input, comp.clone());
-				} else {
-					trace!(
-						"Cache hit on GivenUp and same states limit ({})",
-						explored_states
-					);
-				}
-			}
-
 			Some(cached_comp)
 		} else {
 			debug!("Cache miss");
-			let comp = input.alg.tour_computation(input.clone());
+			let comp = input
+				.alg
+				.tour_computation_with_handle(input.clone(), handle);
+			if handle.is_cancelled() {
+				return None;
+			}
 			add_solution_to_cache(input, comp.clone());
 			Some(comp)
 		}
 	}
 }
 
-use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
 
-/// Actual state of cell
-#[derive(Debug, Clone, PartialEq, Hash, Eq, EnumIs)]
-enum CellState {
-	NeverOccupied { target_allows_finish_here: bool },
-	PreviouslyOccupied,
-}
+use bitboard::{Board, Layout};
+mod bitboard;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Board<'a> {
-	cell_states: BTreeMap<ChessPoint, CellState>,
-	recommended_moves: &'a Moves,
+/// Everything that stays the same while recursing, shared between worker threads
+struct Search<'a> {
+	tour_type: TourType,
+	/// Index of the starting cell in the [Layout]
+	start: usize,
+	limits: &'a Limits,
+	handle: &'a ComputeHandle,
+	pruning: PruningRules,
+	pruned: PruneCounter,
+	/// One counter for all workers, so the safety cap means the same thing with or without threads
+	state_counter: AtomicU64,
+	/// Set once any worker finds a solution, so the others can stop
+	found: AtomicBool,
+	/// When enumerating, called with every solution instead of stopping at the first
+	on_solution: Option<Mutex<OnSolution<'a>>>,
 }
 
-impl<'a> Board<'a> {
-	fn get(&self, p: &ChessPoint) -> Option<CellState> {
-		self.cell_states.get(p).cloned()
-	}
-	fn set(&mut self, p: ChessPoint, state: CellState) {
-		self.cell_states.insert(p, state);
-	}
-
-	fn from_options(options: &'a BoardOptions) -> Self {
-		Board {
-			recommended_moves: options.recommended_moves(),
-			cell_states: options
-				.get_available_points()
-				.into_iter()
-				.map(|p| {
-					(
-						p,
-						CellState::NeverOccupied {
-							target_allows_finish_here: options.get(&p).unwrap().unwrap_available(),
-						},
-					)
-				})
-				.collect(),
-		}
-	}
+/// Receives each solution found while enumerating, see [enumerate_tours]
+type OnSolution<'a> = &'a mut (dyn FnMut(Moves) -> ControlFlow<()> + Send);
 
-	fn get_unrecommended_moves_from(
-		&self,
-		p: &ChessPoint,
-		piece: &ChessPiece,
-		allow_start_location: &ChessPoint,
-	) -> std::collections::HashSet<ChessPoint> {
-		let mut moves = std::collections::HashSet::new();
-		for &(dx, dy) in piece.relative_moves() {
-			if let Some(p) = p.displace(&(dx, dy)) {
-				if self.get(&p).map(|s| s.is_never_occupied()) == Some(true) {
-					moves.insert(p);
-				} else if p == *allow_start_location {
-					moves.insert(p);
-				}
-			}
-		}
-		moves
-	}
-
-	/// Also takes into account recommended moves
-	fn get_available_moves_from(
-		&self,
-		p: &ChessPoint,
-		piece: &ChessPiece,
-		allow_start_location: &ChessPoint,
-	) -> Vec<ChessPoint> {
-		let mut moves = self.get_unrecommended_moves_from(p, piece, allow_start_location);
-		// take into account recommended moves
-		if !self.recommended_moves.is_empty() {
-			// relevant moves from start
-			let relevant_recommendations: std::collections::HashSet<ChessPoint> = self
-				.recommended_moves
-				.iter()
-				.filter_map(|recommended_move| {
-					if recommended_move.from == *p {
-						Some(recommended_move.to)
-					} else if recommended_move.to == *p {
-						Some(recommended_move.from)
-					} else {
-						None
-					}
-				})
-				.collect();
-			// if any valid moves are contained in the recommendations, we must prioritise them
-			if relevant_recommendations.len() != 0
-				&& moves.intersection(&relevant_recommendations).count() > 0
-			{
-				debug!(message = "Moves before", ?moves);
-				moves = moves
-					.into_iter()
-					.filter(|p| relevant_recommendations.contains(p))
-					.collect();
-				debug!(message = "Moves after", ?moves);
-			}
-		}
+impl Search<'_> {
+	/// How many levels at the top of the search tree are split across threads
+	const PARALLEL_DEPTH: u8 = 2;
 
-		Vec::from_iter(moves)
+	fn next_state(&self) -> u128 {
+		self.state_counter.fetch_add(1, Ordering::Relaxed) as u128 + 1
 	}
-	fn get_degree(&self, p: &ChessPoint, piece: &ChessPiece) -> u16 {
-		let mut degree = 0;
-		for &(dx, dy) in piece.relative_moves() {
-			if let Some(p) = p.displace(&(dx, dy)) {
-				if self.get(&p).map(|s| s.is_never_occupied()) == Some(true) {
-					degree += 1;
-				}
-			}
-		}
-		degree
+
+	fn explored_states(&self) -> u128 {
+		self.state_counter.load(Ordering::Relaxed) as u128
 	}
 }
 
-/// Recursively solves a knights tour
+/// Recursively solves a knights tour.
+/// `board` is left as it was found, `current` is marked as visited only while exploring its children.
+/// The first `parallel_depth` levels explore their children on separate threads (native only)
 fn try_move_recursive(
-	tour_type: TourType,
+	search: &Search,
 	num_moves_required: u16,
-	piece: &ChessPiece,
-	attempting_board: Board,
-	current_pos: ChessPoint,
-	starting_position: ChessPoint,
-	state_counter: &mut u128,
-	state_cap: u128,
+	board: &mut Board,
+	current: usize,
+	parallel_depth: u8,
 ) -> PartialComputation {
-	*state_counter += 1;
-	if *state_counter >= state_cap {
+	if search.found.load(Ordering::Relaxed) {
+		// another worker has already found a solution, so this result is ignored anyway.
+		// Checked before the limits, so hitting them now can't hide that solution
+		return PartialComputation::Failed;
+	}
+	let state_counter = search.next_state();
+	if let Some(reason) = search.limits.check(state_counter, search.handle) {
 		// base case to avoid excessive computation
-		return PartialComputation::GivenUp;
+		return PartialComputation::GivenUp(reason);
 	}
 
 	if num_moves_required == 0 {
 		// base case
-		if let Some(state) = attempting_board.get(&current_pos) {
-			match (tour_type, state) {
-				(TourType::WeakOpen | TourType::BruteForceOpen, CellState::PreviouslyOccupied) => {
-					panic!("What, trying to end on point already moved to?")
-				}
-				// If you can finish on this square.
-				// If a target is present, this may be false.
-				// this check rejects solutions that don't end on a target.
-				(
-					TourType::WeakOpen | TourType::BruteForceOpen,
-					CellState::NeverOccupied {
-						target_allows_finish_here: true,
-					},
-				) => {
-					return PartialComputation::Successful {
-						solution: vec![].into(),
-					};
-				}
-				(
-					TourType::WeakOpen | TourType::BruteForceOpen,
-					CellState::NeverOccupied {
-						target_allows_finish_here: false,
-					},
-				) => {
-					return PartialComputation::Failed;
-				}
-				(TourType::BruteForceClosed, CellState::NeverOccupied { .. }) => {
-					return PartialComputation::Failed; // For brute force closed we must end at the end
-				}
-				(TourType::BruteForceClosed, CellState::PreviouslyOccupied) => {
-					if current_pos == starting_position {
-						return PartialComputation::Successful {
-							solution: vec![].into(),
-						};
-					} else {
-						panic!("The only time we should end on a previously occupied square is when we are back at the start.");
-					}
-				}
+		let finished = match search.tour_type {
+			// If you can finish on this square.
+			// If a target is present, this may be false.
+			// this check rejects solutions that don't end on a target.
+			TourType::WeakOpen | TourType::BruteForceOpen => board.finishable().contains(current),
+			// For brute force closed we must end at the start
+			TourType::BruteForceClosed => current == search.start,
+		};
+		if !finished {
+			return PartialComputation::Failed;
+		}
+		if let Some(on_solution) = &search.on_solution {
+			let mut path = board.path().to_vec();
+			path.push(current);
+			// a closed tour is found once in each direction, only keep one of them
+			if matches!(search.tour_type, TourType::BruteForceClosed) && path[1] > path[path.len() - 2] {
+				return PartialComputation::Failed;
+			}
+			let moves = path_to_moves(board.layout, &path);
+			if (on_solution.lock().unwrap())(moves).is_continue() {
+				// keep backtracking to find the next one
+				return PartialComputation::Failed;
 			}
 		}
+		search.found.store(true, Ordering::Relaxed);
+		return PartialComputation::Successful {
+			solution: vec![].into(),
+		};
 	}
 
-	let mut available_moves =
-		attempting_board.get_available_moves_from(&current_pos, piece, &starting_position);
+	if let Some(rule) = board.prune(current, search) {
+		search.pruned.add(rule);
+		return PartialComputation::Failed;
+	}
+
+	// only a closed tour's last move may go back to the start
+	let allow_start_location = match search.tour_type {
+		TourType::BruteForceClosed if num_moves_required == 1 => Some(search.start),
+		_ => None,
+	};
+	let mut available_moves = board.get_available_moves_from(current, allow_start_location);
 	if available_moves.is_empty() {
 		// stuck, no where to move
 		return PartialComputation::Failed;
@@ -409,94 +520,147 @@ fn try_move_recursive(
 
 	// sort by degree
 	// this implicitely applies Warnsdorf algorithm
-	available_moves.sort_by_cached_key(|p| attempting_board.get_degree(p, piece));
+	available_moves.sort_by_cached_key(|i| board.get_degree(*i));
 
-	match tour_type {
+	match search.tour_type {
 		TourType::WeakOpen => {
 			// IMPORTANT: Only considers moves with the lowest degree. To make brute force, remove this
-			let lowest_degree = attempting_board.get_degree(&available_moves[0], piece);
-			available_moves.retain(|p| attempting_board.get_degree(p, piece) == lowest_degree);
+			let lowest_degree = board.get_degree(available_moves[0]);
+			available_moves.retain(|i| board.get_degree(*i) == lowest_degree);
 		}
 		TourType::BruteForceOpen | TourType::BruteForceClosed => {}
 	}
 
-	let mut moves = None;
+	// imagine making the move
+	board.visit(current);
+	let result = try_next_moves(
+		search,
+		num_moves_required,
+		board,
+		current,
+		available_moves,
+		parallel_depth,
+	);
+	board.unvisit(current);
 
-	for potential_next_move in available_moves {
-		let mut board_with_potential_move = attempting_board.clone();
+	result
+}
 
-		// imagine making the move
-		board_with_potential_move.set(current_pos, CellState::PreviouslyOccupied);
+/// Recurses into each of `available_moves` from `current`, until one doesn't fail
+fn try_next_moves(
+	search: &Search,
+	num_moves_required: u16,
+	board: &mut Board,
+	current: usize,
+	available_moves: Vec<usize>,
+	parallel_depth: u8,
+) -> PartialComputation {
+	let layout = board.layout;
+	// initially, working_moves will be empty
+	// first iteration must add move from current to next
+	// this repeats
+	let add_move = |next: usize| {
+		move |mut working_moves: Moves| {
+			working_moves.push(Move::new(layout.point(current), layout.point(next)));
+			working_moves
+		}
+	};
+
+	#[cfg(not(target_arch = "wasm32"))]
+	if parallel_depth > 0 {
+		use rayon::prelude::*;
+
+		// the first branch to succeed wins, only giving up if none did
+		let board = &*board;
+		let given_up = Mutex::new(None);
+		let solution = available_moves.into_par_iter().find_map_any(|next| {
+			// each worker gets its own copy of the board
+			let mut board = board.clone();
+			match try_move_recursive(
+				search,
+				num_moves_required - 1,
+				&mut board,
+				next,
+				parallel_depth - 1,
+			) {
+				PartialComputation::Failed => None,
+				PartialComputation::GivenUp(reason) => {
+					given_up.lock().unwrap().get_or_insert(reason);
+					None
+				}
+				result => Some(result.map(add_move(next))),
+			}
+		});
+		return solution
+			.or_else(|| given_up.into_inner().unwrap().map(PartialComputation::GivenUp))
+			.unwrap_or(PartialComputation::Failed);
+	}
 
+	for next in available_moves {
 		// now imagine the future of making the move (recursion)
-		let result = try_move_recursive(
-			tour_type.clone(),
-			num_moves_required - 1,
-			piece,
-			board_with_potential_move,
-			potential_next_move,
-			starting_position,
-			state_counter,
-			state_cap,
-		);
-
-		match result {
+		match try_move_recursive(search, num_moves_required - 1, board, next, 0) {
 			PartialComputation::Failed => { /* Continue looping, try to find a non-failed solution */ }
-			PartialComputation::Successful {
-				solution: mut working_moves,
-			} => {
-				// initially, working_moves will be empty
-				// first iteration must add move from current_pos to potential_next_move
-				// this repeats
-				working_moves.push(Move::new(current_pos, potential_next_move));
-
-				// found a solution, set to moves, stop looping and return success!
-				moves = Some(working_moves);
-				break;
-			}
-			PartialComputation::GivenUp => {
-				// If a child recursive call has reached the call stack limit, give up as well
-				return PartialComputation::GivenUp;
-			}
-		};
+			// found a solution, stop looping and return success!
+			// If a child recursive call has reached the call stack limit, give up as well
+			result => return result.map(add_move(next)),
+		}
 	}
 
-	PartialComputation::from(moves)
+	PartialComputation::Failed
 }
 
 #[derive(Clone, Copy)]
 enum TourType {
 	/// Does not always find solution but is significantly faster
-	/// Used by [Algorithm::WarnsdorfBacktrack]
+	/// Used by [WarnsdorfBacktrack]
 	WeakOpen,
 	/// Always finds solution but is significantly slower
 	BruteForceOpen,
-	/// [Algorithm::HamiltonianBruteForce]
+	/// Used by [HamiltonianBruteForce]
 	BruteForceClosed,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn brute_recursive_repeatless(
 	piece: &ChessPiece,
 	options: BoardOptions,
 	start: ChessPoint,
 	tour_type: TourType,
-	safety_cap: u128,
+	limits: Limits,
+	pruning: PruningRules,
+	handle: &ComputeHandle,
+	on_solution: Option<OnSolution>,
 ) -> Computation {
-	let all_available_points = options.get_available_points();
-	let num_moves_required = all_available_points.len() as u16 - 1;
-
-	let mut state_counter = 0_u128;
-
-	let board = Board::from_options(&options);
-	try_move_recursive(
+	let layout = Layout::new(&options, piece);
+	let num_moves_required = match tour_type {
+		TourType::WeakOpen | TourType::BruteForceOpen => layout.len() as u16 - 1,
+		// including the move back to the start
+		TourType::BruteForceClosed => layout.len() as u16,
+	};
+
+	let search = Search {
 		tour_type,
+		start: layout.index(&start).unwrap(),
+		limits: &limits,
+		handle,
+		pruning,
+		pruned: PruneCounter::default(),
+		state_counter: AtomicU64::new(0),
+		found: AtomicBool::new(false),
+		on_solution: on_solution.map(|f| Mutex::new(f as OnSolution)),
+	};
+	let parallel_depth = match tour_type {
+		TourType::BruteForceOpen | TourType::BruteForceClosed => Search::PARALLEL_DEPTH,
+		TourType::WeakOpen => 0,
+	};
+
+	let mut board = Board::new(&layout, &options);
+	try_move_recursive(
+		&search,
 		num_moves_required,
-		piece,
-		board,
-		start,
-		start,
-		&mut state_counter,
-		safety_cap,
+		&mut board,
+		search.start,
+		parallel_depth,
 	)
 	.map(|moves| {
 		let mut moves = moves.into_iter().rev().collect::<Vec<Move>>();
@@ -504,7 +668,19 @@ fn brute_recursive_repeatless(
 		moves.push(Move::new(*end, *end));
 		moves.into()
 	})
-	.add_state_count(state_counter)
+	.add_state_count(search.explored_states(), search.pruned.counts(), &limits)
+}
+
+/// Same shape as the solutions [brute_recursive_repeatless] returns,
+/// including the trailing move from the last cell to itself
+fn path_to_moves(layout: &Layout, path: &[usize]) -> Moves {
+	let mut moves: Vec<Move> = path
+		.windows(2)
+		.map(|w| Move::new(layout.point(w[0]), layout.point(w[1])))
+		.collect();
+	let end = layout.point(*path.last().unwrap());
+	moves.push(Move::new(end, end));
+	moves.into()
 }
 
 use cache::add_solution_to_cache;
@@ -539,3 +715,40 @@ mod cache {
 		cache.put(options, moves);
 	}
 }
+
+#[test]
+fn pruning_never_loses_solutions() {
+	for (width, height) in [(3, 4), (4, 4), (3, 5), (3, 7)] {
+		let options = BoardOptions::new(width, height);
+		for start in options.get_available_points() {
+			for alg in ["brute-force", "hamiltonian-brute-force"] {
+				let input = |pruning| OwnedComputeInput {
+					alg: Algorithm::from_id(alg).unwrap(),
+					safety_cap: None,
+					time_budget: None,
+					pruning,
+					start,
+					board_options: options.clone(),
+					piece: ChessPiece::default(),
+				};
+				let pruned = input(PruningRules::default());
+				let unpruned = input(PruningRules::NONE);
+				assert_eq!(
+					matches!(
+						pruned.alg.tour_computation(pruned.clone()),
+						Computation::Successful { .. }
+					),
+					matches!(
+						unpruned.alg.tour_computation(unpruned.clone()),
+						Computation::Successful { .. }
+					),
+					"{} from {} on {}x{}",
+					alg,
+					start,
+					width,
+					height
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,261 @@
+//! Compact board used by the brute force solvers.
+//! Available cells are numbered once up front, so the hot loop only ever flips bits
+//! instead of cloning maps of [ChessPoint]s
+
+use std::collections::HashMap;
+
+use super::{BoardOptions, ChessPiece, ChessPoint, Moves};
+
+/// Set of cell indices, sized for one [Layout]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct BitSet {
+	words: Vec<u64>,
+}
+
+impl BitSet {
+	pub(super) fn new(len: usize) -> Self {
+		Self {
+			words: vec![0; len.div_ceil(64)],
+		}
+	}
+
+	/// Returns whether `i` was newly inserted
+	pub(super) fn insert(&mut self, i: usize) -> bool {
+		let bit = 1 << (i % 64);
+		let word = &mut self.words[i / 64];
+		let new = *word & bit == 0;
+		*word |= bit;
+		new
+	}
+
+	pub(super) fn remove(&mut self, i: usize) {
+		self.words[i / 64] &= !(1 << (i % 64));
+	}
+
+	pub(super) fn contains(&self, i: usize) -> bool {
+		self.words[i / 64] & (1 << (i % 64)) != 0
+	}
+
+	pub(super) fn len(&self) -> usize {
+		self.words.iter().map(|w| w.count_ones() as usize).sum()
+	}
+
+	pub(super) fn intersection_len(&self, other: &BitSet) -> usize {
+		self
+			.words
+			.iter()
+			.zip(other.words.iter())
+			.map(|(a, b)| (a & b).count_ones() as usize)
+			.sum()
+	}
+
+	pub(super) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		self.iter_words(|i| self.words[i])
+	}
+
+	pub(super) fn intersection<'a>(&'a self, other: &'a BitSet) -> impl Iterator<Item = usize> + 'a {
+		self.iter_words(|i| self.words[i] & other.words[i])
+	}
+
+	fn iter_words<'a>(
+		&'a self,
+		word: impl Fn(usize) -> u64 + 'a,
+	) -> impl Iterator<Item = usize> + 'a {
+		(0..self.words.len()).flat_map(move |i| {
+			let mut bits = word(i);
+			std::iter::from_fn(move || {
+				if bits == 0 {
+					return None;
+				}
+				let bit = bits.trailing_zeros() as usize;
+				bits &= bits - 1;
+				Some(i * 64 + bit)
+			})
+		})
+	}
+}
+
+/// Everything about the board and piece that doesn't change during a search.
+/// Cell indices are the positions in [BoardOptions::get_available_points],
+/// and moves wrap around the board's [super::Topology]
+#[derive(Debug)]
+pub(super) struct Layout {
+	points: Vec<ChessPoint>,
+	indices: HashMap<ChessPoint, usize>,
+	/// Neighbour masks, cells the piece can move to from each cell
+	moves_from: Vec<BitSet>,
+	/// Cells the piece can move to each cell from, differs from [Self::moves_from]
+	/// for pieces with asymmetric moves
+	moves_to: Vec<BitSet>,
+}
+
+impl Layout {
+	pub(super) fn new(options: &BoardOptions, piece: &ChessPiece) -> Self {
+		let points = options.get_available_points();
+		let indices: HashMap<ChessPoint, usize> =
+			points.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+
+		let mut moves_from = vec![BitSet::new(points.len()); points.len()];
+		let mut moves_to = vec![BitSet::new(points.len()); points.len()];
+		for (from, p) in points.iter().enumerate() {
+			for to in piece
+				.destinations(*p, options)
+				.iter()
+				.filter_map(|to| indices.get(to))
+			{
+				moves_from[from].insert(*to);
+				moves_to[*to].insert(from);
+			}
+		}
+
+		Self {
+			points,
+			indices,
+			moves_from,
+			moves_to,
+		}
+	}
+
+	pub(super) fn len(&self) -> usize {
+		self.points.len()
+	}
+
+	pub(super) fn index(&self, p: &ChessPoint) -> Option<usize> {
+		self.indices.get(p).copied()
+	}
+
+	pub(super) fn point(&self, i: usize) -> ChessPoint {
+		self.points[i]
+	}
+
+	pub(super) fn moves_from(&self, i: usize) -> &BitSet {
+		&self.moves_from[i]
+	}
+
+	pub(super) fn moves_to(&self, i: usize) -> &BitSet {
+		&self.moves_to[i]
+	}
+}
+
+/// State of a search, mutated in place as moves are made and undone
+#[derive(Debug, Clone)]
+pub(super) struct Board<'a> {
+	pub(super) layout: &'a Layout,
+	unvisited: BitSet,
+	/// Cells the tour may finish on, see [super::CellOption::Available]
+	finishable: BitSet,
+	/// As index pairs, recommended moves between unavailable cells are dropped
+	recommended_moves: Vec<(usize, usize)>,
+	/// Visited cells, in the order they were visited
+	path: Vec<usize>,
+}
+
+impl<'a> Board<'a> {
+	pub(super) fn new(layout: &'a Layout, options: &BoardOptions) -> Self {
+		let mut unvisited = BitSet::new(layout.len());
+		let mut finishable = BitSet::new(layout.len());
+		for i in 0..layout.len() {
+			unvisited.insert(i);
+			if options.get(&layout.point(i)).unwrap().unwrap_available() {
+				finishable.insert(i);
+			}
+		}
+
+		Self {
+			layout,
+			unvisited,
+			finishable,
+			recommended_moves: recommended_indices(layout, options.recommended_moves()),
+			path: Vec::with_capacity(layout.len()),
+		}
+	}
+
+	pub(super) fn unvisited(&self) -> &BitSet {
+		&self.unvisited
+	}
+
+	pub(super) fn finishable(&self) -> &BitSet {
+		&self.finishable
+	}
+
+	pub(super) fn is_unvisited(&self, i: usize) -> bool {
+		self.unvisited.contains(i)
+	}
+
+	pub(super) fn path(&self) -> &[usize] {
+		&self.path
+	}
+
+	/// Must be undone with [Self::unvisit] in reverse order
+	pub(super) fn visit(&mut self, i: usize) {
+		self.unvisited.remove(i);
+		self.path.push(i);
+	}
+
+	pub(super) fn unvisit(&mut self, i: usize) {
+		self.unvisited.insert(i);
+		let last = self.path.pop();
+		debug_assert_eq!(last, Some(i));
+	}
+
+	/// Number of unvisited cells the piece could move to from `i`
+	pub(super) fn get_degree(&self, i: usize) -> usize {
+		self.layout.moves_from(i).intersection_len(&self.unvisited)
+	}
+
+	/// Also takes into account recommended moves.
+	/// `allow_start_location` is only given for the last move of a closed tour
+	pub(super) fn get_available_moves_from(
+		&self,
+		i: usize,
+		allow_start_location: Option<usize>,
+	) -> Vec<usize> {
+		let mut moves: Vec<usize> = self
+			.layout
+			.moves_from(i)
+			.iter()
+			.filter(|to| self.is_unvisited(*to) || Some(*to) == allow_start_location)
+			.collect();
+
+		// take into account recommended moves
+		// if any valid moves are contained in the recommendations, we must prioritise them
+		let is_recommended = |to: &usize| {
+			self
+				.recommended_moves
+				.iter()
+				.any(|&(a, b)| (a == i && b == *to) || (b == i && a == *to))
+		};
+		if moves.iter().any(is_recommended) {
+			moves.retain(is_recommended);
+		}
+
+		moves
+	}
+}
+
+fn recommended_indices(layout: &Layout, moves: &Moves) -> Vec<(usize, usize)> {
+	moves
+		.iter()
+		.filter_map(|m| Some((layout.index(&m.from)?, layout.index(&m.to)?)))
+		.collect()
+}
+
+#[test]
+fn bitset_iterates_in_order() {
+	let mut set = BitSet::new(200);
+	for i in [0, 5, 63, 64, 130, 199] {
+		assert!(set.insert(i));
+	}
+	assert!(!set.insert(5));
+	set.remove(63);
+
+	assert_eq!(set.len(), 5);
+	assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 5, 64, 130, 199]);
+
+	let mut other = BitSet::new(200);
+	other.insert(64);
+	other.insert(199);
+	other.insert(3);
+	assert_eq!(set.intersection(&other).collect::<Vec<_>>(), vec![64, 199]);
+	assert_eq!(set.intersection_len(&other), 2);
+}
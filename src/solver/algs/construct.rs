@@ -0,0 +1,313 @@
+//! Closed knight's tours for boards far too large to search.
+//! The board is cut into blocks small enough to brute force once, and neighbouring blocks are
+//! joined Parberry style: one move in each block is swapped for two moves across their shared edge,
+//! which turns two closed tours into one. Linear in the number of cells
+
+use std::{collections::HashMap, ops::Range, sync::Mutex};
+
+use once_cell::sync::Lazy;
+
+use super::*;
+
+/// Largest board the constructor is offered for in the UI, it works for any size
+pub const MAX_CONSTRUCTED_SIZE: u8 = 100;
+
+/// Smallest side a block can have
+const MIN_BLOCK: usize = 5;
+
+const KNIGHT_MOVES: [(isize, isize); 8] = [
+	(1, 2),
+	(2, 1),
+	(2, -1),
+	(1, -2),
+	(-1, -2),
+	(-2, -1),
+	(-2, 1),
+	(-1, 2),
+];
+
+/// (row, column), 0 indexed
+type Cell = (usize, usize);
+
+/// Closed tour of every cell of a `width` x `height` board, starting and ending on `start`.
+/// Shaped like the solutions of the other solvers, with a trailing move from `start` to itself.
+///
+/// Returns [None] if the board has no closed knight's tour (both sides odd, or a side under 5),
+/// because blocks are never smaller than that
+pub fn construct_closed_tour(width: u16, height: u16, start: ChessPoint) -> Option<Moves> {
+	let (width, height) = (width as usize, height as usize);
+	if width < MIN_BLOCK || height < MIN_BLOCK || (width % 2 == 1 && height % 2 == 1) {
+		return None;
+	}
+	// blocks are never odd by odd
+	let (columns, rows) = if width % 2 == 0 {
+		(split_even(width), split_any(height))
+	} else {
+		(split_any(width), split_even(height))
+	};
+
+	let mut tour = Tour::new(width, height);
+	for rows in rows.iter() {
+		for columns in columns.iter() {
+			tour.place_block(rows.clone(), columns.clone())?;
+		}
+	}
+
+	// join each row of blocks from left to right, then the rows from top to bottom
+	for rows in rows.iter() {
+		for pair in columns.windows(2) {
+			tour.join(
+				Rect::new(rows.clone(), pair[0].clone()),
+				Rect::new(rows.clone(), pair[1].clone()),
+			)?;
+		}
+	}
+	for pair in rows.windows(2) {
+		tour.join(
+			Rect::new(pair[0].clone(), 0..width),
+			Rect::new(pair[1].clone(), 0..width),
+		)?;
+	}
+
+	let start = (start.row as usize - 1, start.column as usize - 1);
+	let cycle = tour.cycle_from(start);
+	let point = |(row, column): Cell| ChessPoint::new(row as u16 + 1, column as u16 + 1);
+	let mut moves: Vec<Move> = cycle
+		.iter()
+		.zip(cycle.iter().skip(1).chain(std::iter::once(&start)))
+		.map(|(from, to)| Move::new(point(*from), point(*to)))
+		.collect();
+	moves.push(Move::new(point(start), point(start)));
+	Some(moves.into())
+}
+
+/// Ranges of even length, each 6, 8 or 10 long
+fn split_even(len: usize) -> Vec<Range<usize>> {
+	let mut lens = Vec::new();
+	let mut left = len;
+	while left >= 16 {
+		lens.push(8);
+		left -= 8;
+	}
+	match left {
+		12 => lens.extend([6, 6]),
+		14 => lens.extend([8, 6]),
+		_ => lens.push(left),
+	}
+	to_ranges(lens)
+}
+
+/// Ranges between 5 and 9 long
+fn split_any(len: usize) -> Vec<Range<usize>> {
+	let mut lens = Vec::new();
+	let mut left = len;
+	while left >= 14 {
+		lens.push(8);
+		left -= 8;
+	}
+	if left > 9 {
+		lens.extend([left / 2, left - left / 2]);
+	} else {
+		lens.push(left);
+	}
+	to_ranges(lens)
+}
+
+fn to_ranges(lens: Vec<usize>) -> Vec<Range<usize>> {
+	let mut start = 0;
+	lens
+		.into_iter()
+		.map(|len| {
+			start += len;
+			start - len..start
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone)]
+struct Rect {
+	rows: Range<usize>,
+	columns: Range<usize>,
+}
+
+impl Rect {
+	fn new(rows: Range<usize>, columns: Range<usize>) -> Self {
+		Self { rows, columns }
+	}
+
+	fn contains(&self, (row, column): Cell) -> bool {
+		self.rows.contains(&row) && self.columns.contains(&column)
+	}
+
+	/// Cells of `self` within a knight's move of `other`, which must be directly right of or below it
+	fn border_with(&self, other: &Rect) -> impl Iterator<Item = Cell> {
+		let (rows, columns) = if self.columns.end == other.columns.start {
+			(self.rows.clone(), self.columns.end - 2..self.columns.end)
+		} else {
+			debug_assert_eq!(self.rows.end, other.rows.start);
+			(self.rows.end - 2..self.rows.end, self.columns.clone())
+		};
+		rows.flat_map(move |row| columns.clone().map(move |column| (row, column)))
+	}
+}
+
+/// Every cell is linked to the two cells it is moved to and from,
+/// so the tour may be made of several separate cycles while blocks are being joined
+struct Tour {
+	width: usize,
+	height: usize,
+	links: Vec<[Cell; 2]>,
+}
+
+impl Tour {
+	fn new(width: usize, height: usize) -> Self {
+		Self {
+			width,
+			height,
+			links: vec![[(0, 0); 2]; width * height],
+		}
+	}
+
+	fn index(&self, (row, column): Cell) -> usize {
+		row * self.width + column
+	}
+
+	fn links(&self, cell: Cell) -> [Cell; 2] {
+		self.links[self.index(cell)]
+	}
+
+	fn relink(&mut self, cell: Cell, old: Cell, new: Cell) {
+		let i = self.index(cell);
+		let link = self.links[i].iter_mut().find(|c| **c == old).unwrap();
+		*link = new;
+	}
+
+	fn knight_moves(&self, (row, column): Cell) -> impl Iterator<Item = Cell> + '_ {
+		KNIGHT_MOVES.iter().filter_map(move |(dr, dc)| {
+			let row = row.checked_add_signed(*dr)?;
+			let column = column.checked_add_signed(*dc)?;
+			(row < self.height && column < self.width).then_some((row, column))
+		})
+	}
+
+	/// Copies the tour of a block that size onto `rows` x `columns`
+	fn place_block(&mut self, rows: Range<usize>, columns: Range<usize>) -> Option<()> {
+		let block = block_tour(columns.len(), rows.len())?;
+		let offset = |(row, column): Cell| (row + rows.start, column + columns.start);
+		for (i, cell) in block.iter().enumerate() {
+			let before = block[(i + block.len() - 1) % block.len()];
+			let after = block[(i + 1) % block.len()];
+			let index = self.index(offset(*cell));
+			self.links[index] = [offset(before), offset(after)];
+		}
+		Some(())
+	}
+
+	/// Joins the separate cycles through `a` and `b` into one,
+	/// by replacing a move `a1 - a2` in `a` and `b1 - b2` in `b` with `a1 - b1` and `a2 - b2`
+	fn join(&mut self, a: Rect, b: Rect) -> Option<()> {
+		let [a1, a2, b1, b2] = self.find_join(&a, &b)?;
+		self.relink(a1, a2, b1);
+		self.relink(a2, a1, b2);
+		self.relink(b1, b2, a1);
+		self.relink(b2, b1, a2);
+		Some(())
+	}
+
+	fn find_join(&self, a: &Rect, b: &Rect) -> Option<[Cell; 4]> {
+		for a1 in a.border_with(b) {
+			for a2 in self.links(a1).into_iter().filter(|c| a.contains(*c)) {
+				for b1 in self.knight_moves(a1).filter(|c| b.contains(*c)) {
+					for b2 in self.links(b1).into_iter().filter(|c| b.contains(*c)) {
+						if self.knight_moves(a2).any(|c| c == b2) {
+							return Some([a1, a2, b1, b2]);
+						}
+					}
+				}
+			}
+		}
+		None
+	}
+
+	/// Follows the links from `start` until they come back around
+	fn cycle_from(&self, start: Cell) -> Vec<Cell> {
+		let mut cycle = Vec::with_capacity(self.links.len());
+		let mut previous = start;
+		let mut current = start;
+		loop {
+			cycle.push(current);
+			let [first, second] = self.links(current);
+			let next = if first != previous { first } else { second };
+			previous = current;
+			current = next;
+			if current == start {
+				return cycle;
+			}
+		}
+	}
+}
+
+/// Closed tours of each block size, brute forced the first time they are needed
+static BLOCKS: Lazy<Mutex<Blocks>> = Lazy::new(|| Mutex::new(HashMap::new()));
+type Blocks = HashMap<(usize, usize), Option<Vec<Cell>>>;
+
+fn block_tour(width: usize, height: usize) -> Option<Vec<Cell>> {
+	let mut blocks = BLOCKS.lock().unwrap();
+	blocks
+		.entry((width, height))
+		.or_insert_with(|| {
+			let options = BoardOptions::new(width as u16, height as u16);
+			match brute_recursive_repeatless(
+				&ChessPiece::default(),
+				options,
+				ChessPoint::new(1, 1),
+				TourType::BruteForceClosed,
+				Limits::new(None, None),
+				PruningRules::default(),
+				&ComputeHandle::default(),
+				None,
+			) {
+				Computation::Successful { solution, .. } => Some(
+					solution
+						.iter()
+						.take(width * height)
+						.map(|m| (m.from.row as usize - 1, m.from.column as usize - 1))
+						.collect(),
+				),
+				comp => {
+					error!(
+						"No closed tour for a {}x{} block: {:?}",
+						width, height, comp
+					);
+					None
+				}
+			}
+		})
+		.clone()
+}
+
+#[test]
+fn constructs_closed_tours_on_large_boards() {
+	let knight = ChessPiece::default();
+	for (width, height) in [(6, 5), (7, 10), (12, 12), (31, 20), (100, 100)] {
+		let start = ChessPoint::new(height / 2, width / 3);
+		let moves = construct_closed_tour(width, height, start).unwrap();
+		let cells = width as usize * height as usize;
+		// every cell once, then back to the start
+		assert_eq!(moves.len(), cells + 1);
+		assert_eq!(moves[0].from, start);
+		assert_eq!(moves[cells - 1].to, start);
+		let visited: std::collections::HashSet<_> = moves.iter().map(|m| m.from).collect();
+		assert_eq!(visited.len(), cells);
+		for m in moves.iter().take(cells) {
+			assert!(
+				knight.is_valid_move(m.from, m.to, &BoardOptions::new(width, height)),
+				"{} is not a knight move",
+				m
+			);
+		}
+	}
+
+	assert!(construct_closed_tour(9, 11, ChessPoint::new(1, 1)).is_none());
+	assert!(construct_closed_tour(4, 20, ChessPoint::new(1, 1)).is_none());
+}
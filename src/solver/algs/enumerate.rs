@@ -0,0 +1,155 @@
+//! Finding every tour instead of just the first, on the same backtracking core as the brute force solvers
+
+use std::ops::ControlFlow;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::*;
+
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, strum::Display,
+)]
+pub enum TourKind {
+	#[default]
+	Open,
+	/// Each tour is only found once, not once per direction
+	Closed,
+}
+
+/// Summary of an [enumerate_tours] run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enumeration {
+	pub found: u64,
+	pub explored_states: u128,
+	/// Why the search stopped before finding every tour, if it did
+	pub given_up: Option<GiveUpReason>,
+	/// Whether `on_solution` asked to stop early
+	pub stopped: bool,
+	pub pruned: PruneCounts,
+}
+
+impl Enumeration {
+	/// Whether [Self::found] is the total number of tours
+	pub fn is_complete(&self) -> bool {
+		self.given_up.is_none() && !self.stopped
+	}
+}
+
+/// Calls `on_solution` with every tour of `kind` from `input.start`, in search order.
+/// Respects the safety cap, time budget, pruning rules, targets and recommended moves of `input`
+pub fn enumerate_tours(
+	input: &OwnedComputeInput,
+	kind: TourKind,
+	handle: &ComputeHandle,
+	mut on_solution: impl FnMut(Moves) -> ControlFlow<()> + Send,
+) -> Enumeration {
+	let mut found = 0;
+	let mut counting = |moves| {
+		found += 1;
+		on_solution(moves)
+	};
+	let tour_type = match kind {
+		TourKind::Open => TourType::BruteForceOpen,
+		TourKind::Closed => TourType::BruteForceClosed,
+	};
+
+	let comp = brute_recursive_repeatless(
+		&input.piece,
+		input.board_options.clone(),
+		input.start,
+		tour_type,
+		Limits::from(input),
+		input.pruning,
+		handle,
+		Some(&mut counting),
+	);
+	let pruned = comp.pruned();
+	match comp {
+		Computation::Successful {
+			explored_states, ..
+		} => Enumeration {
+			found,
+			explored_states,
+			given_up: None,
+			stopped: true,
+			pruned,
+		},
+		Computation::Failed { total_states, .. } => Enumeration {
+			found,
+			explored_states: total_states,
+			given_up: None,
+			stopped: false,
+			pruned,
+		},
+		Computation::GivenUp {
+			explored_states,
+			reason,
+			..
+		} => Enumeration {
+			found,
+			explored_states,
+			given_up: Some(reason),
+			stopped: false,
+			pruned,
+		},
+	}
+}
+
+/// Counts the tours of `kind` from `input.start`, see [enumerate_tours]
+pub fn count_tours(
+	input: &OwnedComputeInput,
+	kind: TourKind,
+	handle: &ComputeHandle,
+) -> Enumeration {
+	enumerate_tours(input, kind, handle, |_| ControlFlow::Continue(()))
+}
+
+/// Iterator over every tour, searching on a background thread as it is consumed.
+/// Dropping it stops the search
+#[cfg(not(target_arch = "wasm32"))]
+pub fn tours(input: OwnedComputeInput, kind: TourKind) -> impl Iterator<Item = Moves> {
+	let (sender, receiver) = std::sync::mpsc::sync_channel(0);
+	std::thread::spawn(move || {
+		enumerate_tours(&input, kind, &ComputeHandle::default(), |moves| {
+			match sender.send(moves) {
+				Ok(()) => ControlFlow::Continue(()),
+				// receiver dropped
+				Err(_) => ControlFlow::Break(()),
+			}
+		})
+	});
+	receiver.into_iter()
+}
+
+#[test]
+fn counts_open_and_closed_tours() {
+	let input = |board_options| OwnedComputeInput {
+		alg: Algorithm::default(),
+		safety_cap: None,
+		time_budget: None,
+		pruning: PruningRules::default(),
+		start: ChessPoint::new(1, 1),
+		board_options,
+		piece: ChessPiece::default(),
+	};
+	let handle = ComputeHandle::default();
+
+	// the 3x4 board has exactly two open tours from a corner
+	let enumeration = count_tours(&input(BoardOptions::new(4, 3)), TourKind::Open, &handle);
+	assert!(enumeration.is_complete());
+	assert_eq!(enumeration.found, 2);
+
+	// and the 3x10 board is the smallest 3 row board with closed tours, it has 16
+	let enumeration = count_tours(&input(BoardOptions::new(10, 3)), TourKind::Closed, &handle);
+	assert!(enumeration.is_complete());
+	assert_eq!(enumeration.found, 16);
+	let enumeration = count_tours(&input(BoardOptions::new(8, 3)), TourKind::Closed, &handle);
+	assert_eq!(enumeration.found, 0);
+
+	let first_two: Vec<Moves> = tours(input(BoardOptions::new(10, 3)), TourKind::Closed)
+		.take(2)
+		.collect();
+	assert_eq!(first_two.len(), 2);
+	assert_ne!(first_two[0], first_two[1]);
+}
@@ -7,44 +7,58 @@ use crate::{
 	ChessPoint,
 };
 
-use super::Computation;
+use super::{Computation, ComputeHandle, GiveUpReason, Limits, PruneCounts};
 
 type Key = u32;
 type Graph = HashMap<Key, HashSet<Key>>;
 type Path = Vec<Key>;
 
+/// What a [Path] must do once it has visited every vertex
+enum Goal {
+	/// Move back to the start
+	Cycle { start: Key },
+	/// Finish on one of these, usually the targets
+	Path { ends: HashSet<Key> },
+}
+
 #[allow(non_snake_case)]
 fn find_hamiltonian_path(
-	end: u32,
+	goal: &Goal,
 	P: &Path,
 	g: &Graph,
 	state_counter: &mut u128,
-	safety_cap: u128,
-) -> Result<Option<Path>, ()> {
+	limits: &Limits,
+	handle: &ComputeHandle,
+) -> Result<Option<Path>, GiveUpReason> {
 	*state_counter += 1;
-	if *state_counter >= safety_cap {
-		return Err(());
+	if let Some(reason) = limits.check(*state_counter, handle) {
+		return Err(reason);
 	}
 
 	let v = P.last().unwrap();
-	if P.len() == g.len() && g.get(v).unwrap().contains(&end) {
-		let mut C = P.clone();
-		C.push(end);
-		Ok(Some(C))
-	} else {
-		for w in g.get(v).unwrap() {
-			if P.contains(w) {
-				continue;
-			}
-			let mut Q = P.clone();
-			Q.push(*w);
-			let H = find_hamiltonian_path(end, &Q, g, state_counter, safety_cap)?;
-			if H.is_some() {
-				return Ok(H);
-			}
+	if P.len() == g.len() {
+		return Ok(match goal {
+			Goal::Cycle { start } => g.get(v).unwrap().contains(start).then(|| {
+				let mut C = P.clone();
+				C.push(*start);
+				C
+			}),
+			Goal::Path { ends } => ends.contains(v).then(|| P.clone()),
+		});
+	}
+
+	for w in g.get(v).unwrap() {
+		if P.contains(w) {
+			continue;
+		}
+		let mut Q = P.clone();
+		Q.push(*w);
+		let H = find_hamiltonian_path(goal, &Q, g, state_counter, limits, handle)?;
+		if H.is_some() {
+			return Ok(H);
 		}
-		Ok(None)
 	}
+	Ok(None)
 }
 
 impl ChessPoint {
@@ -59,13 +73,15 @@ impl ChessPoint {
 	}
 }
 
-/// cycle paramter: whether to find Hamiltonian Cycle or Path
+/// cycle paramter: whether to find Hamiltonian Cycle or Path.
+/// Paths have to end on a target, if there are any
 pub fn hamiltonian_tour_repeatless(
 	piece: &ChessPiece,
 	options: BoardOptions,
 	start: ChessPoint,
-	safety_cap: u128,
+	limits: Limits,
 	cycle: bool,
+	handle: &ComputeHandle,
 ) -> Computation {
 	assert!(options.get_available_points().contains(&start));
 
@@ -77,15 +93,12 @@ pub fn hamiltonian_tour_repeatless(
 	}
 
 	let mut graph: Graph = HashMap::new();
-	let valid_moves = piece.relative_moves();
 	for point in available_points.iter() {
 		let mut edges: HashSet<Key> = HashSet::new();
-		for d in valid_moves.iter() {
-			if let Some(point) = point.displace(d) {
-				if available_mapped_points.contains_key(&point) {
-					// if point is adjacent, exists, and is available, it is valid edge
-					edges.insert(*available_mapped_points.get(&point).unwrap());
-				}
+		for point in piece.destinations(*point, &options) {
+			if let Some(key) = available_mapped_points.get(&point) {
+				// if point is reachable, exists, and is available, it is valid edge
+				edges.insert(*key);
 			}
 		}
 		graph.insert(*available_mapped_points.get(point).unwrap(), edges);
@@ -99,60 +112,82 @@ pub fn hamiltonian_tour_repeatless(
 	);
 
 	let start = *available_mapped_points.get(&start).unwrap();
-	let start_vec = vec![start];
-	if !cycle {
-		// show any path that works
-		let mut state_counter: u128 = 0;
-		for valid_end_point in available_mapped_points.values() {
-			match find_hamiltonian_path(*valid_end_point, &start_vec, &graph, &mut state_counter, safety_cap) {
-				Err(_) => return Computation::Failed { total_states: 0 },
-				Ok(None) => continue,
-				Ok(Some(mut path)) => {
-					path.pop();
-
-					let moves: Moves = path
-						.into_iter()
-						.map(ChessPoint::un_hash)
-						.tuple_windows()
-						.map(Move::from_tuple)
-						.collect();
-
-					return Computation::Successful {
-						solution: moves,
-						explored_states: state_counter,
-					};
-				}
-			}
-		}
-		Computation::Failed { total_states: 0 }
+	let goal = if cycle {
+		Goal::Cycle { start }
 	} else {
-		let mut state_counter: u128 = 0;
-		match find_hamiltonian_path(start, &start_vec, &graph, &mut state_counter, safety_cap) {
-			Err(_) => Computation::GivenUp {
+		// respect targets
+		Goal::Path {
+			ends: available_points
+				.iter()
+				.filter(|p| options.get(p).unwrap().unwrap_available())
+				.map(|p| *available_mapped_points.get(p).unwrap())
+				.collect(),
+		}
+	};
+
+	let mut state_counter: u128 = 0;
+	match find_hamiltonian_path(
+		&goal,
+		&vec![start],
+		&graph,
+		&mut state_counter,
+		&limits,
+		handle,
+	) {
+		Err(reason) => Computation::GivenUp {
+			explored_states: state_counter,
+			reason,
+			elapsed: limits.elapsed(),
+			pruned: PruneCounts::default(),
+		},
+		Ok(None) => Computation::Failed {
+			total_states: state_counter,
+			pruned: PruneCounts::default(),
+			impossible: None,
+		},
+		Ok(Some(path)) => {
+			// a cycle ends back up at start
+			let expected_len = available_points.len() + if cycle { 1 } else { 0 };
+			assert_eq!(expected_len, path.len());
+
+			debug!("Path found: {:?}", path);
+
+			let moves: Moves = path
+				.into_iter()
+				.map(ChessPoint::un_hash)
+				.tuple_windows()
+				.map(Move::from_tuple)
+				.collect();
+
+			Computation::Successful {
+				solution: moves,
 				explored_states: state_counter,
-			},
-			Ok(None) => Computation::Failed {
-				total_states: state_counter,
-			},
-			Ok(Some(path)) => {
-				// show only cycle
-				assert_eq!(available_points.len(), path.len() - 1);
-
-				debug!("Path found: {:?}", path);
-				// path always ends back up at start
-
-				let moves: Moves = path
-					.into_iter()
-					.map(ChessPoint::un_hash)
-					.tuple_windows()
-					.map(Move::from_tuple)
-					.collect();
-
-				Computation::Successful {
-					solution: moves,
-					explored_states: 0,
-				}
+				pruned: PruneCounts::default(),
 			}
 		}
 	}
 }
+
+#[test]
+fn hamiltonian_path_visits_every_cell() {
+	let options = BoardOptions::new(5, 5);
+	let start = ChessPoint::new(1, 1);
+	match hamiltonian_tour_repeatless(
+		&ChessPiece::default(),
+		options,
+		start,
+		Limits::new(Some(1_000_000), None),
+		false,
+		&ComputeHandle::default(),
+	) {
+		Computation::Successful {
+			solution,
+			explored_states,
+			..
+		} => {
+			assert_eq!(solution.len(), 24);
+			assert!(explored_states > 0);
+		}
+		comp => panic!("Expected a path, got {:?}", comp),
+	}
+}
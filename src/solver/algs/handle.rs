@@ -0,0 +1,50 @@
+use std::sync::{
+	atomic::{AtomicBool, AtomicU64, Ordering},
+	Arc,
+};
+
+/// Shared between a running computation and whoever started it,
+/// so progress can be watched and the computation cancelled from another thread.
+///
+/// Solvers should call [ComputeHandle::set_explored_states] as they go,
+/// and give up as soon as [ComputeHandle::is_cancelled] is true.
+#[derive(Debug, Clone, Default)]
+pub struct ComputeHandle(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+	explored_states: AtomicU64,
+	cancelled: AtomicBool,
+	finished: AtomicBool,
+}
+
+impl ComputeHandle {
+	pub fn cancel(&self) {
+		self.0.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.cancelled.load(Ordering::Relaxed)
+	}
+
+	pub fn set_explored_states(&self, states: u128) {
+		self
+			.0
+			.explored_states
+			.store(states.try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+	}
+
+	/// Latest count reported by the solver
+	pub fn explored_states(&self) -> u128 {
+		self.0.explored_states.load(Ordering::Relaxed) as u128
+	}
+
+	/// Called by the runner once the computation has returned
+	pub fn finish(&self) {
+		self.0.finished.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.0.finished.load(Ordering::Relaxed)
+	}
+}
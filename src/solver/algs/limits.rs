@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use instant::Instant;
+use serde::{Deserialize, Serialize};
+
+use super::{ComputeHandle, OwnedComputeInput};
+
+/// Why a computation returned [super::Computation::GivenUp]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+pub enum GiveUpReason {
+	#[strum(serialize = "hit the safety cap")]
+	SafetyCap,
+	#[strum(serialize = "ran out of time")]
+	TimeBudget,
+	#[strum(serialize = "was cancelled")]
+	Cancelled,
+}
+
+/// Keeps track of when a running computation should give up.
+/// Constructed when the computation starts, from [OwnedComputeInput::safety_cap] and
+/// [OwnedComputeInput::time_budget]
+#[derive(Debug, Clone)]
+pub struct Limits {
+	safety_cap: Option<u128>,
+	time_budget: Option<Duration>,
+	started: Instant,
+}
+
+impl Limits {
+	/// Reading the clock is relatively slow, especially on wasm,
+	/// so the time budget is only checked every this many states
+	const TIME_CHECK_INTERVAL: u128 = 256;
+
+	pub fn new(safety_cap: Option<u128>, time_budget: Option<Duration>) -> Self {
+		Self {
+			safety_cap,
+			time_budget,
+			started: Instant::now(),
+		}
+	}
+
+	pub fn elapsed(&self) -> Duration {
+		self.started.elapsed()
+	}
+
+	/// Call once per explored state, after incrementing the state counter.
+	/// Also reports progress to the `handle`
+	pub fn check(&self, explored_states: u128, handle: &ComputeHandle) -> Option<GiveUpReason> {
+		handle.set_explored_states(explored_states);
+		if handle.is_cancelled() {
+			return Some(GiveUpReason::Cancelled);
+		}
+		if let Some(cap) = self.safety_cap {
+			if explored_states >= cap {
+				return Some(GiveUpReason::SafetyCap);
+			}
+		}
+		if let Some(budget) = self.time_budget {
+			if explored_states.is_multiple_of(Self::TIME_CHECK_INTERVAL) && self.elapsed() >= budget {
+				return Some(GiveUpReason::TimeBudget);
+			}
+		}
+		None
+	}
+}
+
+impl From<&OwnedComputeInput> for Limits {
+	fn from(input: &OwnedComputeInput) -> Self {
+		Self::new(input.safety_cap, input.time_budget)
+	}
+}
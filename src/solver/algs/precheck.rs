@@ -0,0 +1,340 @@
+//! Quick analysis that runs before any search, to rule out boards where a tour provably can't exist.
+//! Every check is a necessary condition, so a board that passes may still have no tour
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use super::{bitboard::Layout, *};
+
+/// Why no tour can exist, shown instead of searching
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Impossible {
+	/// Schwenk's theorem, for closed standard knight's tours of full flat boards
+	Schwenk {
+		width: u16,
+		height: u16,
+	},
+	/// Closed tours of pieces that always change colour
+	OddCells {
+		cells: usize,
+	},
+	/// Only for pieces that always change colour
+	ColourImbalance {
+		dark: usize,
+		light: usize,
+	},
+	/// Open tours with one more cell of one colour have to start on that colour
+	WrongStartColour {
+		majority: Colour,
+	},
+	Disconnected {
+		components: usize,
+	},
+	/// Open tours can only have one cell with a single move besides the start
+	TooManyDeadEnds {
+		dead_ends: usize,
+	},
+	/// Both ends of an open tour are taken by cells with a single move
+	StartNotOnDeadEnd {
+		dead_ends: [ChessPoint; 2],
+	},
+	/// A closed tour has to move into and out of every cell
+	ClosedDeadEnd {
+		cell: ChessPoint,
+	},
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Colour {
+	Dark,
+	Light,
+}
+
+impl Colour {
+	/// The bottom left cell is dark, like a real chess board
+	fn of(p: &ChessPoint) -> Self {
+		if (p.row + p.column).is_multiple_of(2) {
+			Self::Dark
+		} else {
+			Self::Light
+		}
+	}
+}
+
+impl Display for Impossible {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Schwenk { width, height } => write!(
+				f,
+				"Schwenk's theorem rules out closed knight's tours on a {}x{} board",
+				width, height
+			),
+			Self::OddCells { cells } => write!(
+				f,
+				"A closed tour alternates colours, so can't cover an odd number of cells ({})",
+				cells
+			),
+			Self::ColourImbalance { dark, light } => write!(
+				f,
+				"Every move changes colour, but there are {} dark and {} light cells",
+				dark, light
+			),
+			Self::WrongStartColour { majority } => write!(
+				f,
+				"There is one more {} cell, so the tour has to start on a {} cell",
+				majority, majority
+			),
+			Self::Disconnected { components } => write!(
+				f,
+				"The cells are split into {} groups the piece can't move between",
+				components
+			),
+			Self::TooManyDeadEnds { dead_ends } => write!(
+				f,
+				"{} cells can only be reached by one move, but a tour only has two ends",
+				dead_ends
+			),
+			Self::StartNotOnDeadEnd { dead_ends: [a, b] } => write!(
+				f,
+				"{} and {} can only be reached by one move, so the tour has to start on one of them",
+				a, b
+			),
+			Self::ClosedDeadEnd { cell } => write!(
+				f,
+				"{} can only be reached by one move, so can't be part of a closed tour",
+				cell
+			),
+		}
+	}
+}
+
+/// Everything about a board that doesn't depend on the start, worked out once so every cell
+/// can be checked cheaply with [Self::impossible_from]
+pub struct Precheck {
+	layout: Layout,
+	/// Rules out every start
+	impossible: Option<Impossible>,
+	kind: TourKind,
+	/// Cells with a single move in and out, only for pieces with symmetric moves
+	dead_ends: Vec<ChessPoint>,
+	/// Number of dark and light cells, only for pieces that always change colour
+	colours: Option<(usize, usize)>,
+}
+
+impl Precheck {
+	pub fn new(options: &BoardOptions, piece: &ChessPiece, kind: TourKind) -> Self {
+		let layout = Layout::new(options, piece);
+		let cells = layout.len();
+
+		let alternates_colour = piece.always_changes_colour()
+			&& options
+				.topology()
+				.preserves_colours(options.width(), options.height());
+		let colours = alternates_colour.then(|| {
+			let dark = (0..cells)
+				.filter(|i| Colour::of(&layout.point(*i)) == Colour::Dark)
+				.count();
+			(dark, cells - dark)
+		});
+
+		let dead_ends = if piece.is_symmetric() {
+			(0..cells)
+				.filter(|i| layout.moves_from(*i).len() == 1)
+				.map(|i| layout.point(i))
+				.collect()
+		} else {
+			Vec::new()
+		};
+
+		let mut precheck = Self {
+			layout,
+			impossible: None,
+			kind,
+			dead_ends,
+			colours,
+		};
+		// a single cell is always a tour, and an empty board has no start
+		if cells > 1 {
+			precheck.impossible = precheck.check_board(options, piece);
+		}
+		precheck
+	}
+
+	fn check_board(&self, options: &BoardOptions, piece: &ChessPiece) -> Option<Impossible> {
+		let (width, height) = (options.width(), options.height());
+		let full = self.layout.len() == width as usize * height as usize;
+		if self.kind == TourKind::Closed
+			&& full
+			&& options.topology() == Topology::Flat
+			&& *piece == ChessPiece::default()
+		{
+			let (m, n) = (width.min(height), width.max(height));
+			if (m % 2 == 1 && n % 2 == 1) || [1, 2, 4].contains(&m) || (m == 3 && [4, 6, 8].contains(&n))
+			{
+				return Some(Impossible::Schwenk { width, height });
+			}
+		}
+
+		if let Some((dark, light)) = self.colours {
+			match self.kind {
+				TourKind::Closed if (dark + light) % 2 == 1 => {
+					return Some(Impossible::OddCells {
+						cells: dark + light,
+					});
+				}
+				TourKind::Closed if dark != light => {
+					return Some(Impossible::ColourImbalance { dark, light });
+				}
+				TourKind::Open if dark.abs_diff(light) > 1 => {
+					return Some(Impossible::ColourImbalance { dark, light });
+				}
+				_ => {}
+			}
+		}
+
+		let components = self.components();
+		if components > 1 {
+			return Some(Impossible::Disconnected { components });
+		}
+
+		match self.kind {
+			TourKind::Open if self.dead_ends.len() > 2 => Some(Impossible::TooManyDeadEnds {
+				dead_ends: self.dead_ends.len(),
+			}),
+			TourKind::Closed if !self.dead_ends.is_empty() => Some(Impossible::ClosedDeadEnd {
+				cell: self.dead_ends[0],
+			}),
+			_ => None,
+		}
+	}
+
+	/// Number of groups of cells that are connected by moves in either direction
+	fn components(&self) -> usize {
+		let layout = &self.layout;
+		let mut seen = vec![false; layout.len()];
+		let mut components = 0;
+		for first in 0..layout.len() {
+			if seen[first] {
+				continue;
+			}
+			components += 1;
+			seen[first] = true;
+			let mut frontier = vec![first];
+			while let Some(i) = frontier.pop() {
+				for j in layout.moves_from(i).iter().chain(layout.moves_to(i).iter()) {
+					if !seen[j] {
+						seen[j] = true;
+						frontier.push(j);
+					}
+				}
+			}
+		}
+		components
+	}
+
+	/// Why no tour can start on `start`, if that is certain.
+	/// Returns [None] for unavailable cells
+	pub fn impossible_from(&self, start: ChessPoint) -> Option<Impossible> {
+		self.layout.index(&start)?;
+		if self.impossible.is_some() {
+			return self.impossible.clone();
+		}
+		if self.kind == TourKind::Closed {
+			return None;
+		}
+
+		if let Some((dark, light)) = self.colours {
+			if dark != light {
+				let majority = if dark > light {
+					Colour::Dark
+				} else {
+					Colour::Light
+				};
+				if Colour::of(&start) != majority {
+					return Some(Impossible::WrongStartColour { majority });
+				}
+			}
+		}
+
+		if let [a, b] = self.dead_ends[..] {
+			if start != a && start != b {
+				return Some(Impossible::StartNotOnDeadEnd { dead_ends: [a, b] });
+			}
+		}
+
+		None
+	}
+}
+
+/// Checks the board, piece and start of `input` for the kind of tour its solver looks for
+pub fn precheck(input: &OwnedComputeInput) -> Option<Impossible> {
+	Precheck::new(&input.board_options, &input.piece, input.alg.tour_kind())
+		.impossible_from(input.start)
+}
+
+#[test]
+fn precheck_explains_impossible_boards() {
+	let knight = ChessPiece::default();
+	let check = |width, height, kind, start| {
+		Precheck::new(&BoardOptions::new(width, height), &knight, kind).impossible_from(start)
+	};
+	let corner = ChessPoint::new(1, 1);
+
+	assert_eq!(
+		check(4, 6, TourKind::Closed, corner),
+		Some(Impossible::Schwenk {
+			width: 4,
+			height: 6
+		})
+	);
+	assert_eq!(check(6, 6, TourKind::Closed, corner), None);
+	assert_eq!(check(10, 3, TourKind::Closed, corner), None);
+
+	// 5x5 has one more dark cell, and only dark starts have open tours
+	assert_eq!(check(5, 5, TourKind::Open, corner), None);
+	assert_eq!(
+		check(5, 5, TourKind::Open, ChessPoint::new(1, 2)),
+		Some(Impossible::WrongStartColour {
+			majority: Colour::Dark
+		})
+	);
+
+	// the middle of a 3x3 board can't be reached at all
+	assert_eq!(
+		check(3, 3, TourKind::Open, corner),
+		Some(Impossible::Disconnected { components: 2 })
+	);
+
+	// every cell of a 2x5 board is a dead end or isolated
+	assert!(matches!(
+		check(5, 2, TourKind::Open, corner),
+		Some(Impossible::Disconnected { .. })
+	));
+
+	// prechecks never rule out a board brute force can solve
+	for (width, height) in [(3, 4), (4, 5), (5, 5), (3, 7)] {
+		let options = BoardOptions::new(width, height);
+		let precheck = Precheck::new(&options, &knight, TourKind::Open);
+		for start in options.get_available_points() {
+			let found = count_tours(
+				&OwnedComputeInput {
+					alg: Algorithm::default(),
+					safety_cap: None,
+					time_budget: None,
+					pruning: PruningRules::default(),
+					start,
+					board_options: options.clone(),
+					piece: knight.clone(),
+				},
+				TourKind::Open,
+				&ComputeHandle::default(),
+			)
+			.found;
+			if precheck.impossible_from(start).is_some() {
+				assert_eq!(found, 0, "{}x{} from {}", width, height, start);
+			}
+		}
+	}
+}
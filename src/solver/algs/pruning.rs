@@ -0,0 +1,195 @@
+//! Rules that reject hopeless branches of the brute force search early.
+//! Every rule is a necessary condition for the remaining cells to be tourable,
+//! so pruning never loses a solution
+
+use std::{
+	fmt::{self, Display},
+	sync::atomic::{AtomicU64, Ordering},
+};
+
+#[cfg(feature = "gui")]
+use bevy::reflect::{FromReflect, Reflect};
+use serde::{Deserialize, Serialize};
+
+use super::{
+	bitboard::{BitSet, Board},
+	Search, TourType,
+};
+
+/// Which pruning rules the brute force solvers apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
+pub struct PruningRules {
+	/// Every unvisited cell must still be reachable from the current cell
+	pub connectivity: bool,
+	/// Every unvisited cell needs a way in, and at most one cell may have no way out
+	pub dead_ends: bool,
+	/// Some reachable cell must still be able to end the tour.
+	/// For open tours that is a target (or any cell without targets),
+	/// for closed tours a cell that can move back to the start
+	pub unreachable_end: bool,
+}
+
+impl Default for PruningRules {
+	fn default() -> Self {
+		Self {
+			connectivity: true,
+			dead_ends: true,
+			unreachable_end: true,
+		}
+	}
+}
+
+impl PruningRules {
+	pub const NONE: Self = Self {
+		connectivity: false,
+		dead_ends: false,
+		unreachable_end: false,
+	};
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum PruneRule {
+	Connectivity,
+	DeadEnds,
+	UnreachableEnd,
+}
+
+/// How many branches each rule cut, reported in every [super::Computation]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneCounts {
+	pub connectivity: u64,
+	pub dead_ends: u64,
+	pub unreachable_end: u64,
+}
+
+impl PruneCounts {
+	pub fn total(&self) -> u64 {
+		self.connectivity + self.dead_ends + self.unreachable_end
+	}
+}
+
+impl Display for PruneCounts {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Pruned {} disconnected, {} dead end and {} unreachable end branches",
+			self.connectivity, self.dead_ends, self.unreachable_end
+		)
+	}
+}
+
+/// Shared between worker threads, see [Search]
+#[derive(Default)]
+pub(super) struct PruneCounter {
+	connectivity: AtomicU64,
+	dead_ends: AtomicU64,
+	unreachable_end: AtomicU64,
+}
+
+impl PruneCounter {
+	pub(super) fn add(&self, rule: PruneRule) {
+		match rule {
+			PruneRule::Connectivity => &self.connectivity,
+			PruneRule::DeadEnds => &self.dead_ends,
+			PruneRule::UnreachableEnd => &self.unreachable_end,
+		}
+		.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub(super) fn counts(&self) -> PruneCounts {
+		PruneCounts {
+			connectivity: self.connectivity.load(Ordering::Relaxed),
+			dead_ends: self.dead_ends.load(Ordering::Relaxed),
+			unreachable_end: self.unreachable_end.load(Ordering::Relaxed),
+		}
+	}
+}
+
+impl Board<'_> {
+	/// Checks whether the unvisited cells can still be toured starting from `current`,
+	/// returning the first rule that rejects this board
+	pub(super) fn prune(&self, current: usize, search: &Search) -> Option<PruneRule> {
+		let rules = search.pruning;
+		let layout = self.layout;
+		let unvisited = self.unvisited();
+		// cells the tour could end on
+		let ends = match search.tour_type {
+			TourType::BruteForceClosed => layout.moves_to(search.start),
+			TourType::WeakOpen | TourType::BruteForceOpen => self.finishable(),
+		};
+
+		// current is still unvisited
+		let remaining = unvisited.len() - 1;
+		if remaining == 0 {
+			// the last move is checked by the base case
+			return None;
+		}
+
+		if rules.connectivity || rules.unreachable_end {
+			// flood fill from the current cell
+			let mut reached = BitSet::new(layout.len());
+			reached.insert(current);
+			let mut frontier = vec![current];
+			while let Some(p) = frontier.pop() {
+				for q in layout.moves_from(p).intersection(unvisited) {
+					if reached.insert(q) {
+						frontier.push(q);
+					}
+				}
+			}
+
+			if rules.connectivity && reached.len() <= remaining {
+				return Some(PruneRule::Connectivity);
+			}
+			if rules.unreachable_end && !reached.intersection(ends).any(|p| p != current) {
+				return Some(PruneRule::UnreachableEnd);
+			}
+		}
+
+		if rules.dead_ends {
+			// cells that can't be passed through have to be the last cell
+			let mut must_end = 0;
+			for v in unvisited.iter().filter(|v| *v != current) {
+				let mut ins = layout.moves_to(v).intersection(unvisited);
+				let Some(first_in) = ins.next() else {
+					return Some(PruneRule::DeadEnds);
+				};
+				let many_ins = ins.next().is_some();
+
+				// needs to be entered from one cell and left to a different one
+				let can_pass_through = layout
+					.moves_from(v)
+					.intersection(unvisited)
+					.any(|out| out != current && (many_ins || out != first_in));
+				if !can_pass_through {
+					must_end += 1;
+					if must_end > 1 || !ends.contains(v) {
+						return Some(PruneRule::DeadEnds);
+					}
+				}
+			}
+		}
+
+		None
+	}
+}
+
+#[cfg(feature = "gui")]
+mod ui {
+	use super::*;
+	use bevy_egui::egui::Ui;
+
+	impl PruningRules {
+		pub fn ui(&mut self, ui: &mut Ui) {
+			ui.label("Pruning (brute force only)");
+			ui.checkbox(&mut self.connectivity, "Connectivity")
+				.on_hover_text("Give up on a path once the unvisited cells are split apart");
+			ui.checkbox(&mut self.dead_ends, "Dead ends")
+				.on_hover_text("Give up on a path once two cells can only be the last cell");
+			ui.checkbox(&mut self.unreachable_end, "Unreachable end").on_hover_text(
+				"Give up on a path once no reachable cell can end the tour (targets, or next to the start for closed tours)",
+			);
+		}
+	}
+}
@@ -0,0 +1,227 @@
+//! Pluggable solvers.
+//! The algorithm picker and hotkeys read from the registry, so a new solver only has to
+//! implement [TourSolver] and be passed to [register_solver].
+
+use std::{
+	fmt::{self, Display},
+	sync::RwLock,
+};
+
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use super::*;
+
+pub trait TourSolver: Send + Sync {
+	/// Stable identifier, used when saving and when selecting from the command line.
+	/// Must be unique among registered solvers
+	fn id(&self) -> &'static str;
+
+	/// Shown in the algorithm picker
+	fn name(&self) -> &'static str;
+
+	fn get_description(&self) -> &'static str;
+
+	/// Lowercase letter that selects this solver, shown as `[x]` after the name.
+	/// Must be unique among registered solvers
+	fn hotkey(&self) -> Option<char> {
+		None
+	}
+
+	/// Which tours this solver finds, used to [precheck] boards before solving
+	fn tour_kind(&self) -> TourKind {
+		TourKind::Open
+	}
+
+	/// Largest width and height the board sliders allow while this solver is selected
+	fn max_board_size(&self) -> u8 {
+		BoardOptions::MAX_SIZE
+	}
+
+	/// Should report progress to, and stop early when cancelled by, the `handle`
+	fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation;
+}
+
+static SOLVERS: Lazy<RwLock<Vec<&'static dyn TourSolver>>> = Lazy::new(|| {
+	let built_in: [&'static dyn TourSolver; 6] = [
+		&BruteForceWarnsford,
+		&WarnsdorfBacktrack,
+		&HamiltonianCycle,
+		&HamiltonianPath,
+		&HamiltonianBruteForce,
+		&Constructed,
+	];
+	RwLock::new(built_in.to_vec())
+});
+
+/// Makes a solver available to the algorithm picker, hotkeys and [Algorithm::from_id].
+/// Ignores solvers whose [TourSolver::id] or [TourSolver::hotkey] is already registered
+pub fn register_solver(solver: &'static dyn TourSolver) {
+	let mut solvers = SOLVERS.write().unwrap();
+	if solvers.iter().any(|s| s.id() == solver.id()) {
+		warn!(
+			"Solver with id {:?} already registered, ignoring",
+			solver.id()
+		);
+		return;
+	}
+	if let Some(taken) = solver
+		.hotkey()
+		.and_then(|key| solvers.iter().find(|s| s.hotkey() == Some(key)))
+	{
+		warn!(
+			"Solver {:?} has the same hotkey as {:?}, ignoring",
+			solver.id(),
+			taken.id()
+		);
+		return;
+	}
+	solvers.push(solver);
+}
+
+/// All registered solvers, in registration order
+pub fn solvers() -> Vec<&'static dyn TourSolver> {
+	SOLVERS.read().unwrap().clone()
+}
+
+fn find_solver(id: &str) -> Option<&'static dyn TourSolver> {
+	SOLVERS
+		.read()
+		.unwrap()
+		.iter()
+		.find(|solver| solver.id() == id)
+		.copied()
+}
+
+/// Handle to a registered [TourSolver], cheap to copy and hash
+#[derive(Copy, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+	feature = "gui",
+	derive(Reflect, FromReflect),
+	reflect_value(PartialEq, Hash)
+)]
+pub struct Algorithm(&'static str);
+
+impl Default for Algorithm {
+	fn default() -> Self {
+		Self(BruteForceWarnsford.id())
+	}
+}
+
+impl From<&'static dyn TourSolver> for Algorithm {
+	fn from(solver: &'static dyn TourSolver) -> Self {
+		Self(solver.id())
+	}
+}
+
+impl Display for Algorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let solver = self.solver();
+		match solver.hotkey() {
+			Some(key) => write!(f, "{} [{}]", solver.name(), key),
+			None => write!(f, "{}", solver.name()),
+		}
+	}
+}
+
+impl Algorithm {
+	pub fn from_id(id: &str) -> Option<Self> {
+		find_solver(id).map(Self::from)
+	}
+
+	/// Every registered solver
+	pub fn all() -> Vec<Self> {
+		SOLVERS
+			.read()
+			.unwrap()
+			.iter()
+			.map(|&solver| solver.into())
+			.collect()
+	}
+
+	pub fn id(&self) -> &'static str {
+		self.0
+	}
+
+	/// Panics if the solver was never registered, which can't happen for an [Algorithm]
+	/// obtained through this module
+	pub fn solver(&self) -> &'static dyn TourSolver {
+		find_solver(self.0).unwrap_or_else(|| panic!("Solver {:?} is not registered", self.0))
+	}
+
+	pub fn hotkey(&self) -> Option<char> {
+		self.solver().hotkey()
+	}
+
+	pub fn get_description(&self) -> &'static str {
+		self.solver().get_description()
+	}
+
+	pub fn max_board_size(&self) -> u8 {
+		self.solver().max_board_size()
+	}
+
+	pub fn tour_kind(&self) -> TourKind {
+		self.solver().tour_kind()
+	}
+
+	pub fn tour_computation(&self, input: OwnedComputeInput) -> Computation {
+		self.tour_computation_with_handle(input, &ComputeHandle::default())
+	}
+
+	/// Skips solving entirely if the [precheck] rules out a tour
+	pub fn tour_computation_with_handle(
+		&self,
+		input: OwnedComputeInput,
+		handle: &ComputeHandle,
+	) -> Computation {
+		if let Some(impossible) = precheck(&input) {
+			return Computation::Failed {
+				total_states: 0,
+				pruned: PruneCounts::default(),
+				impossible: Some(impossible),
+			};
+		}
+		self.solver().tour_computation(input, handle)
+	}
+
+	#[cfg(feature = "gui")]
+	pub fn ui(&mut self, ui: &mut bevy_egui::egui::Ui) {
+		for alg in Self::all() {
+			ui.selectable_value(self, alg, alg.to_string());
+		}
+	}
+}
+
+#[test]
+fn rejects_duplicate_solvers() {
+	struct Duplicate(&'static str, char);
+	impl TourSolver for Duplicate {
+		fn id(&self) -> &'static str {
+			self.0
+		}
+		fn name(&self) -> &'static str {
+			"Duplicate"
+		}
+		fn get_description(&self) -> &'static str {
+			""
+		}
+		fn hotkey(&self) -> Option<char> {
+			Some(self.1)
+		}
+		fn tour_computation(&self, input: OwnedComputeInput, handle: &ComputeHandle) -> Computation {
+			BruteForceWarnsford.tour_computation(input, handle)
+		}
+	}
+
+	let registered = solvers().len();
+	register_solver(&Duplicate("warnsdorf", 'z'));
+	// a new id, but the hotkey of brute force
+	register_solver(&Duplicate("duplicate", 'f'));
+	assert_eq!(solvers().len(), registered);
+	assert_eq!(Algorithm::from_id("duplicate"), None);
+	assert_eq!(
+		Algorithm::from_id("warnsdorf").unwrap().solver().name(),
+		WarnsdorfBacktrack.name()
+	);
+}
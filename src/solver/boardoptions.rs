@@ -1,19 +1,17 @@
 use std::num::NonZeroUsize;
 
-use crate::board::StateInvalidated;
-
 use super::{pieces::ChessPiece, *};
-use bevy::prelude::{FromReflect, Reflect};
-use bevy_egui::*;
+use tracing::{debug, info, trace};
 
 /// Necessary information to make custom board.
 /// Derefs to `Vec<Vec<CellOption>>`, is mutable.
-#[derive(
-	Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Reflect, FromReflect,
-)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 pub struct BoardOptions {
 	options: Vec<Vec<CellOption>>,
 	recommended_moves: Moves,
+	#[serde(default)]
+	topology: Topology,
 }
 
 impl Default for BoardOptions {
@@ -23,50 +21,72 @@ impl Default for BoardOptions {
 }
 
 impl BoardOptions {
-	const MAX_SIZE: u8 = 20;
-	const MIN_SIZE: u8 = 2;
+	pub const MAX_SIZE: u8 = 20;
+	pub const MIN_SIZE: u8 = 2;
+}
 
-	pub fn ui(&mut self, ui: &mut egui::Ui) -> StateInvalidated {
-		let mut state = StateInvalidated::Valid;
+#[cfg(feature = "gui")]
+mod ui {
+	use super::*;
+	use crate::board::StateInvalidated;
+	use bevy_egui::egui;
+	use strum::IntoEnumIterator;
+
+	impl BoardOptions {
+		/// `max_size` depends on the selected solver, see [crate::solver::algs::TourSolver::max_board_size]
+		pub fn ui(&mut self, ui: &mut egui::Ui, max_size: u8) -> StateInvalidated {
+			let mut state = StateInvalidated::Valid;
+
+			ui.add(
+				egui::Slider::from_get_set((Self::MIN_SIZE as f64)..=(max_size as f64), |val| {
+					if let Some(new_val) = val {
+						self.set_width(new_val as u16);
+						state = StateInvalidated::InvalidatedAndClearStart;
+					}
 
-		ui.add(
-			egui::Slider::from_get_set((Self::MIN_SIZE as f64)..=(Self::MAX_SIZE as f64), |val| {
-				if let Some(new_val) = val {
-					self.set_width(new_val as u16);
-					state = StateInvalidated::InvalidatedAndClearStart;
-				}
+					self.width() as f64
+				})
+				.step_by(1.0)
+				.text("Width"),
+			);
 
-				self.width() as f64
-			})
-			.step_by(1.0)
-			.text("Width"),
-		);
+			ui.add(
+				egui::Slider::from_get_set((Self::MIN_SIZE as f64)..=(max_size as f64), |val| {
+					if let Some(new_val) = val {
+						self.set_height(new_val as u16);
+						state = StateInvalidated::InvalidatedAndClearStart;
+					}
 
-		ui.add(
-			egui::Slider::from_get_set((Self::MIN_SIZE as f64)..=(Self::MAX_SIZE as f64), |val| {
-				if let Some(new_val) = val {
-					self.set_height(new_val as u16);
+					self.height() as f64
+				})
+				.step_by(1.0)
+				.text("Height"),
+			);
+
+			ui.horizontal(|ui| {
+				for topology in Topology::iter() {
+					if ui
+						.selectable_value(&mut self.topology, topology, topology.to_string())
+						.changed()
+					{
+						state = StateInvalidated::InvalidatedAndClearStart;
+					}
+				}
+			});
+
+			if self.recommended_moves.len() > 0 {
+				ui.label(format!(
+					"Currently recommending {} moves",
+					self.recommended_moves.len()
+				));
+				if ui.button("Clear all recommended moves?").clicked() {
+					self.clear_recommended_moves();
 					state = StateInvalidated::InvalidatedAndClearStart;
 				}
-
-				self.height() as f64
-			})
-			.step_by(1.0)
-			.text("Height"),
-		);
-
-		if self.recommended_moves.len() > 0 {
-			ui.label(format!(
-				"Currently recommending {} moves",
-				self.recommended_moves.len()
-			));
-			if ui.button("Clear all recommended moves?").clicked() {
-				self.clear_recommended_moves();
-				state = StateInvalidated::InvalidatedAndClearStart;
 			}
-		}
 
-		state
+			state
+		}
 	}
 }
 
@@ -145,6 +165,7 @@ impl BoardOptions {
 				columns as usize
 			],
 			recommended_moves: Moves::default(),
+			topology: Topology::default(),
 		}
 	}
 
@@ -233,6 +254,69 @@ impl BoardOptions {
 		&self.recommended_moves
 	}
 
+	pub fn topology(&self) -> Topology {
+		self.topology
+	}
+
+	pub fn set_topology(&mut self, topology: Topology) -> &mut Self {
+		self.topology = topology;
+		self
+	}
+
+	/// Moves `from` by a relative move of a piece, wrapping around joined edges.
+	/// [None] if that falls off the board, doesn't check if the cell is available
+	pub fn displace(&self, from: ChessPoint, d: &(i16, i16)) -> Option<ChessPoint> {
+		self.displace_with_seam(from, d).map(|(p, _)| p)
+	}
+
+	/// Also returns the [Seam] crossed on the way, if any
+	pub fn displace_with_seam(
+		&self,
+		from: ChessPoint,
+		(dx, dy): &(i16, i16),
+	) -> Option<(ChessPoint, Seam)> {
+		self.topology.wrap(
+			from.row as i32 + *dx as i32,
+			from.column as i32 + *dy as i32,
+			self.width(),
+			self.height(),
+		)
+	}
+
+	/// The relative move and [Seam] of a move of `piece` that only gets from `from` to `to`
+	/// by wrapping around a joined edge.
+	/// [None] if it can also be made without wrapping, or isn't a move of `piece`
+	pub fn wrapped_move(
+		&self,
+		piece: &ChessPiece,
+		from: ChessPoint,
+		to: ChessPoint,
+	) -> Option<((i16, i16), Seam)> {
+		// a ride wraps the same way as a leap the same distance
+		let steps = self.width().max(self.height()) as i16;
+		// stopping at the first overflow, like [ChessPiece::destinations]
+		let rides = piece.rides().iter().flat_map(|(dx, dy)| {
+			(1..=steps)
+				.map(move |k| Some((dx.checked_mul(k)?, dy.checked_mul(k)?)))
+				.take_while(Option::is_some)
+				.flatten()
+		});
+		let moves: Vec<_> = piece
+			.relative_moves()
+			.iter()
+			.copied()
+			.chain(rides)
+			.filter_map(|d| match self.displace_with_seam(from, &d) {
+				Some((p, seam)) if p == to => Some((d, seam)),
+				_ => None,
+			})
+			.collect();
+		moves
+			.first()
+			.copied()
+			.filter(|_| moves.iter().all(|(_, seam)| seam.is_crossed()))
+	}
+
 	pub fn dimensions(&self) -> (u16, u16) {
 		(self.width(), self.height())
 	}
@@ -397,11 +481,7 @@ impl BoardOptions {
 		start: ChessPoint,
 		piece: &ChessPiece,
 	) -> Vec<ChessPoint> {
-		piece
-			.get_unchecked_relative_points(start)
-			.into_iter()
-			.filter(|p| self.validate_point(p) && self.is_available(p))
-			.collect()
+		piece.destinations(start, self)
 	}
 
 	pub fn get_description(&self) -> String {
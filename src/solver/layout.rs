@@ -0,0 +1,335 @@
+//! Boards drawn as plain text, for designing shapes in text files:
+//! ```text
+//! piece: knight
+//! topology: cylinder
+//! .T.#
+//! ..x.
+//! ```
+//! `.` is available, `#` unavailable, `x` eliminated and `T` a target.
+//! When there are targets, tours can only finish on them, otherwise they can finish anywhere.
+//! The top line is the highest row, like the board is drawn on screen.
+//! Spaces between cells are ignored, and the `key: value` header lines are optional
+
+use super::{
+	pieces::{CustomPiece, Piece, StandardPieces},
+	BoardOptions, CellOption, ChessPoint, Topology,
+};
+use std::{
+	fmt::{self, Display},
+	str::FromStr,
+};
+use strum::IntoEnumIterator;
+
+const AVAILABLE: char = '.';
+const UNAVAILABLE: char = '#';
+const ELIMINATED: char = 'x';
+const TARGET: char = 'T';
+
+/// A board read from text, with the piece if the header named one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+	pub board_options: BoardOptions,
+	pub piece: Option<Piece>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+	/// Lines counted from 1
+	BadCell {
+		line: usize,
+		cell: char,
+	},
+	/// Every row needs as many cells as the first
+	RaggedRow {
+		line: usize,
+		expected: usize,
+		found: usize,
+	},
+	BadHeader {
+		line: usize,
+		header: String,
+	},
+	Empty,
+	/// Each side must be between `min` and `max` cells
+	BadSize {
+		width: usize,
+		height: usize,
+		min: u16,
+		max: u16,
+	},
+}
+
+impl Display for LayoutError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::BadCell { line, cell } => write!(
+				f,
+				"Line {}: {:?} is not a cell, expected one of {}{}{}{}",
+				line, cell, AVAILABLE, UNAVAILABLE, ELIMINATED, TARGET
+			),
+			Self::RaggedRow {
+				line,
+				expected,
+				found,
+			} => write!(
+				f,
+				"Line {}: has {} cells but the rows above have {}",
+				line, found, expected
+			),
+			Self::BadHeader { line, header } => {
+				write!(f, "Line {}: can't understand the header {:?}", line, header)
+			}
+			Self::Empty => write!(f, "No rows of cells were given"),
+			Self::BadSize {
+				width,
+				height,
+				min,
+				max,
+			} => write!(
+				f,
+				"The board is {}x{}, but boards must be between {}x{} and {}x{}",
+				width, height, min, min, max, max
+			),
+		}
+	}
+}
+
+impl std::error::Error for LayoutError {}
+
+fn piece_code(piece: &Piece) -> String {
+	match piece {
+		Piece::Standard(standard) => standard.code(),
+		Piece::Custom(custom) => serde_json::to_string(custom).expect("Cannot serialise piece"),
+	}
+}
+
+/// A [StandardPieces::code] or a copied [CustomPiece]
+fn parse_piece(value: &str) -> Option<Piece> {
+	StandardPieces::from_str(value)
+		.map(Piece::Standard)
+		.ok()
+		.or_else(|| {
+			serde_json::from_str::<CustomPiece>(value)
+				.ok()
+				.map(Piece::Custom)
+		})
+}
+
+fn parse_topology(value: &str) -> Option<Topology> {
+	Topology::iter().find(|topology| format!("{:?}", topology).eq_ignore_ascii_case(value))
+}
+
+/// Boards where no cell can be finished on are written as if every cell could
+pub fn write_layout(options: &BoardOptions, piece: Option<&Piece>) -> String {
+	let mut text = String::new();
+	if let Some(piece) = piece {
+		text += &format!("piece: {}\n", piece_code(piece));
+	}
+	if options.topology() != Topology::Flat {
+		text += &format!(
+			"topology: {}\n",
+			format!("{:?}", options.topology()).to_lowercase()
+		);
+	}
+
+	let has_targets = options.get_available_points().iter().any(|p| {
+		options.get(p)
+			== Some(CellOption::Available {
+				can_finish_on: false,
+			})
+	});
+	for row in (1..=options.height()).rev() {
+		for column in 1..=options.width() {
+			text.push(match options.get(&ChessPoint::new(row, column)).unwrap() {
+				CellOption::Available {
+					can_finish_on: true,
+				} if has_targets => TARGET,
+				CellOption::Available { .. } => AVAILABLE,
+				CellOption::Unavailable => UNAVAILABLE,
+				CellOption::Eliminated => ELIMINATED,
+			});
+		}
+		text.push('\n');
+	}
+	text
+}
+
+/// At most [BoardOptions::MAX_SIZE] on each side, see [read_layout_up_to] for larger boards
+pub fn read_layout(text: &str) -> Result<Layout, LayoutError> {
+	read_layout_up_to(text, BoardOptions::MAX_SIZE as u16)
+}
+
+/// Like [read_layout], for solvers that handle boards up to `max_size` on each side
+pub fn read_layout_up_to(text: &str, max_size: u16) -> Result<Layout, LayoutError> {
+	let mut piece = None;
+	let mut topology = Topology::default();
+	let mut rows: Vec<Vec<char>> = Vec::new();
+	for (index, line) in text.lines().enumerate() {
+		let line_number = index + 1;
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		if let Some((key, value)) = line.split_once(':') {
+			let value = value.trim();
+			let understood = match key.trim().to_lowercase().as_str() {
+				"piece" => parse_piece(value).map(|parsed| piece = Some(parsed)),
+				"topology" => parse_topology(value).map(|parsed| topology = parsed),
+				_ => None,
+			};
+			if understood.is_none() {
+				return Err(LayoutError::BadHeader {
+					line: line_number,
+					header: line.to_string(),
+				});
+			}
+			continue;
+		}
+
+		let row: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+		if let Some(&cell) = row
+			.iter()
+			.find(|c| ![AVAILABLE, UNAVAILABLE, ELIMINATED, TARGET].contains(c))
+		{
+			return Err(LayoutError::BadCell {
+				line: line_number,
+				cell,
+			});
+		}
+		if let Some(first) = rows.first() {
+			if first.len() != row.len() {
+				return Err(LayoutError::RaggedRow {
+					line: line_number,
+					expected: first.len(),
+					found: row.len(),
+				});
+			}
+		}
+		rows.push(row);
+	}
+	if rows.is_empty() {
+		return Err(LayoutError::Empty);
+	}
+
+	let (width, height) = (rows[0].len(), rows.len());
+	let min_size = BoardOptions::MIN_SIZE as u16;
+	let fits = |side: usize| (min_size as usize..=max_size as usize).contains(&side);
+	if !fits(width) || !fits(height) {
+		return Err(LayoutError::BadSize {
+			width,
+			height,
+			min: min_size,
+			max: max_size,
+		});
+	}
+
+	let has_targets = rows.iter().flatten().any(|&c| c == TARGET);
+	let (width, height) = (width as u16, height as u16);
+	let mut board_options = BoardOptions::new(width, height);
+	board_options.set_topology(topology);
+	for (row, cells) in (1..=height).rev().zip(rows) {
+		for (column, cell) in (1..=width).zip(cells) {
+			let state = match cell {
+				TARGET => CellOption::Available {
+					can_finish_on: true,
+				},
+				AVAILABLE => CellOption::Available {
+					can_finish_on: !has_targets,
+				},
+				UNAVAILABLE => CellOption::Unavailable,
+				_ => CellOption::Eliminated,
+			};
+			board_options.set_point(ChessPoint::new(row, column), state);
+		}
+	}
+	Ok(Layout {
+		board_options,
+		piece,
+	})
+}
+
+#[test]
+fn layouts_round_trip() {
+	let text = "piece: camel\ntopology: torus\n.T.#\n..x.\n#...\n";
+	let layout = read_layout(text).unwrap();
+	let options = &layout.board_options;
+	assert_eq!((options.width(), options.height()), (4, 3));
+	assert_eq!(options.topology(), Topology::Torus);
+	assert_eq!(layout.piece, Some(Piece::Standard(StandardPieces::Camel)));
+	assert_eq!(
+		options.get(&ChessPoint::new(3, 2)),
+		Some(CellOption::Available {
+			can_finish_on: true
+		})
+	);
+	assert_eq!(
+		options.get(&ChessPoint::new(3, 1)),
+		Some(CellOption::Available {
+			can_finish_on: false
+		})
+	);
+	assert_eq!(
+		options.get(&ChessPoint::new(3, 4)),
+		Some(CellOption::Unavailable)
+	);
+	assert_eq!(
+		options.get(&ChessPoint::new(2, 3)),
+		Some(CellOption::Eliminated)
+	);
+	assert_eq!(
+		options.get(&ChessPoint::new(1, 1)),
+		Some(CellOption::Unavailable)
+	);
+	assert_eq!(write_layout(options, layout.piece.as_ref()), text);
+
+	// no header, spaced out, and without targets every cell can be finished on
+	let spaced = read_layout("\n . . \n . # \n").unwrap();
+	assert_eq!(spaced.piece, None);
+	assert!(spaced.board_options.targets_state().is_all_finishable());
+	assert_eq!(write_layout(&spaced.board_options, None), "..\n.#\n");
+	assert_eq!(
+		read_layout(&write_layout(&BoardOptions::default(), None))
+			.unwrap()
+			.board_options,
+		BoardOptions::default()
+	);
+
+	let custom = Piece::Custom(CustomPiece {
+		name: "Zebra".into(),
+		piece: StandardPieces::ABKnight(2, 3).into(),
+	});
+	let written = write_layout(&BoardOptions::new(3, 3), Some(&custom));
+	assert_eq!(read_layout(&written).unwrap().piece, Some(custom));
+
+	assert_eq!(
+		read_layout("...\n..\n"),
+		Err(LayoutError::RaggedRow {
+			line: 2,
+			expected: 3,
+			found: 2
+		})
+	);
+	assert_eq!(
+		read_layout("..\n.o\n"),
+		Err(LayoutError::BadCell { line: 2, cell: 'o' })
+	);
+	assert!(matches!(
+		read_layout("shape: round\n.."),
+		Err(LayoutError::BadHeader { line: 1, .. })
+	));
+	assert_eq!(read_layout("piece: knight"), Err(LayoutError::Empty));
+	assert!(matches!(
+		read_layout("...\n"),
+		Err(LayoutError::BadSize {
+			width: 3,
+			height: 1,
+			..
+		})
+	));
+	let wide = ".".repeat(21) + "\n" + &".".repeat(21);
+	assert!(matches!(
+		read_layout(&wide),
+		Err(LayoutError::BadSize { width: 21, .. })
+	));
+	assert!(read_layout_up_to(&wide, 100).is_ok());
+}
@@ -1,5 +1,5 @@
+#[cfg(feature = "gui")]
 use bevy::prelude::*;
-use bevy::prelude::{Reflect, FromReflect};
 use serde::{Deserialize, Serialize};
 use std::{
 	fmt::{self, Display},
@@ -8,6 +8,9 @@ use std::{
 use strum::EnumIs;
 
 pub mod algs;
+pub mod layout;
+pub mod move_graph;
+pub mod notation;
 pub mod pieces;
 
 pub use moves::{Move, Moves};
@@ -16,7 +19,6 @@ mod moves;
 // 1 indexed
 #[derive(
 	Copy,
-	Component,
 	Hash,
 	Clone,
 	Debug,
@@ -26,9 +28,8 @@ mod moves;
 	PartialEq,
 	Serialize,
 	Deserialize,
-	Reflect,
-	FromReflect,
 )]
+#[cfg_attr(feature = "gui", derive(Component, Reflect, FromReflect))]
 pub struct ChessPoint {
 	// Between 1 and COLUMN_SIZE.
 	/// Corresponds to x axis
@@ -66,8 +67,14 @@ impl ChessPoint {
 		}
 	}
 
+	/// Whether this is a white square on a standard chess board
+	pub fn is_white(&self) -> bool {
+		(self.row + self.column + 1) % 2 == 0
+	}
+
+	#[cfg(feature = "gui")]
 	pub fn get_standard_colour(&self) -> Color {
-		if (self.row + self.column + 1) % 2 == 0 {
+		if self.is_white() {
 			Color::WHITE
 		} else {
 			Color::BLACK
@@ -101,8 +108,9 @@ impl std::ops::Add for ChessPoint {
 
 
 #[derive(
-	Debug, Copy, Hash, Clone, PartialEq, Eq, PartialOrd, Ord, EnumIs, Serialize, Deserialize, Reflect, FromReflect
+	Debug, Copy, Hash, Clone, PartialEq, Eq, PartialOrd, Ord, EnumIs, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 /// Solver: Available or Unavailable
 pub enum CellOption {
 	/// Only allows solutions ending on target
@@ -124,3 +132,6 @@ impl CellOption {
 
 mod boardoptions;
 pub use boardoptions::*;
+
+pub use topology::{Seam, Topology};
+mod topology;
@@ -0,0 +1,305 @@
+//! The graph of cells joined by the moves of a piece, and exporting it to Graphviz DOT and GraphML
+//! for analysis in other graph tools.
+//! Nodes are named like `a1`, see [super::notation], and carry their row and column
+
+use std::{collections::HashMap, fmt::Write};
+
+use petgraph::prelude::UnGraph;
+
+use super::{
+	notation::square_name, pieces::ChessPiece, BoardOptions, CellOption, ChessPoint, Move, Moves,
+};
+
+/// Every move of `piece` between available cells.
+/// Moves that can be undone are only listed once, from the smaller cell
+pub fn piece_moves(options: &BoardOptions, piece: &ChessPiece) -> Vec<Move> {
+	moves_and_directed(options, piece).0
+}
+
+/// With whether some move can't be undone.
+/// Each cell's destinations are only found once, then searched for the way back
+fn moves_and_directed(options: &BoardOptions, piece: &ChessPiece) -> (Vec<Move>, bool) {
+	let points = options.get_available_points();
+	// sorted by [ChessPiece::destinations]
+	let destinations: HashMap<ChessPoint, Vec<ChessPoint>> = points
+		.iter()
+		.map(|&from| (from, piece.destinations(from, options)))
+		.collect();
+
+	let mut directed = false;
+	let mut moves = Vec::new();
+	for from in points {
+		for &to in destinations[&from].iter() {
+			let can_undo = destinations[&to].binary_search(&from).is_ok();
+			directed |= !can_undo;
+			if from < to || !can_undo {
+				moves.push(Move::new(from, to));
+			}
+		}
+	}
+	(moves, directed)
+}
+
+/// Available cells, joined by the moves of `piece`
+pub fn move_graph(options: &BoardOptions, piece: &ChessPiece) -> UnGraph<ChessPoint, Move> {
+	let mut graph = UnGraph::new_undirected();
+	let nodes: HashMap<ChessPoint, _> = options
+		.get_available_points()
+		.into_iter()
+		.map(|point| (point, graph.add_node(point)))
+		.collect();
+	for m in piece_moves(options, piece) {
+		graph.add_edge(nodes[&m.from], nodes[&m.to], m);
+	}
+	graph
+}
+
+/// What is written for each cell and move
+struct Exported {
+	/// Available and eliminated cells
+	cells: Vec<(ChessPoint, CellOption)>,
+	/// Whether targets are set, so some cells can't be finished on
+	has_targets: bool,
+	/// With the step of the tour they are taken in, if they are
+	edges: Vec<(Move, Option<usize>)>,
+	/// Only if some move can't be undone
+	directed: bool,
+}
+
+impl Exported {
+	fn new(options: &BoardOptions, piece: &ChessPiece, tour: &Moves) -> Self {
+		let cells = options
+			.get_all_points()
+			.into_iter()
+			.filter_map(|point| match options.get(&point)? {
+				CellOption::Unavailable => None,
+				cell => Some((point, cell)),
+			})
+			.collect();
+		let (moves, directed) = moves_and_directed(options, piece);
+		let mut edges: Vec<(Move, Option<usize>)> = moves.into_iter().map(|m| (m, None)).collect();
+		let mut indices: HashMap<Move, usize> = edges
+			.iter()
+			.enumerate()
+			.map(|(index, (m, _))| (*m, index))
+			.collect();
+
+		// tour moves that aren't moves of the piece, like those made by hand, are still included
+		for (step, m) in tour.iter().enumerate().filter(|(_, m)| m.from != m.to) {
+			let index = indices
+				.get(m)
+				.or_else(|| indices.get(&Move::new(m.to, m.from)).filter(|_| !directed))
+				.copied();
+			// written the way the tour goes
+			match index {
+				Some(index) => edges[index] = (*m, Some(step)),
+				None => {
+					indices.insert(*m, edges.len());
+					edges.push((*m, Some(step)));
+				}
+			}
+		}
+
+		Self {
+			cells,
+			has_targets: options.targets_state().should_show_targets_visual(),
+			edges,
+			directed,
+		}
+	}
+
+	fn is_target(&self, cell: CellOption) -> bool {
+		self.has_targets
+			&& cell
+				== CellOption::Available {
+					can_finish_on: true,
+				}
+	}
+}
+
+/// Cells are positioned as on the board for `neato -n`, and the moves of `tour` are drawn thick and red
+/// with their `step` as an attribute
+pub fn to_dot(options: &BoardOptions, piece: &ChessPiece, tour: &Moves) -> String {
+	let exported = Exported::new(options, piece, tour);
+	let (keyword, edge_op) = if exported.directed {
+		("digraph", "->")
+	} else {
+		("graph", "--")
+	};
+
+	let mut dot = String::new();
+	writeln!(dot, "{} moves {{", keyword).unwrap();
+	writeln!(dot, "\tnode [shape=circle];").unwrap();
+	for (point, cell) in exported.cells.iter() {
+		write!(
+			dot,
+			"\t{name} [label=\"{name}\", row={}, column={}, pos=\"{},{}!\"",
+			point.row,
+			point.column,
+			point.column - 1,
+			point.row - 1,
+			name = square_name(*point)
+		)
+		.unwrap();
+		if exported.is_target(*cell) {
+			write!(dot, ", target=true, peripheries=2").unwrap();
+		}
+		if *cell == CellOption::Eliminated {
+			write!(dot, ", eliminated=true, style=dashed").unwrap();
+		}
+		writeln!(dot, "];").unwrap();
+	}
+	for (m, step) in exported.edges.iter() {
+		write!(
+			dot,
+			"\t{} {} {}",
+			square_name(m.from),
+			edge_op,
+			square_name(m.to)
+		)
+		.unwrap();
+		if let Some(step) = step {
+			write!(dot, " [tour=true, step={}, color=red, penwidth=3]", step).unwrap();
+		}
+		writeln!(dot, ";").unwrap();
+	}
+	dot.push_str("}\n");
+	dot
+}
+
+/// With `row`, `column`, `target` and `eliminated` node data and `tour` and `step` edge data
+pub fn to_graphml(options: &BoardOptions, piece: &ChessPiece, tour: &Moves) -> String {
+	let exported = Exported::new(options, piece, tour);
+
+	let mut xml = String::new();
+	writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+	writeln!(
+		xml,
+		r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+	)
+	.unwrap();
+	for (id, domain, kind, default) in [
+		("row", "node", "int", None),
+		("column", "node", "int", None),
+		("target", "node", "boolean", Some("false")),
+		("eliminated", "node", "boolean", Some("false")),
+		("tour", "edge", "boolean", Some("false")),
+		("step", "edge", "int", None),
+	] {
+		match default {
+			Some(default) => writeln!(
+				xml,
+				r#"	<key id="{id}" for="{}" attr.name="{id}" attr.type="{}"><default>{}</default></key>"#,
+				domain,
+				kind,
+				default,
+				id = id
+			),
+			None => writeln!(
+				xml,
+				r#"	<key id="{id}" for="{}" attr.name="{id}" attr.type="{}"/>"#,
+				domain,
+				kind,
+				id = id
+			),
+		}
+		.unwrap();
+	}
+	writeln!(
+		xml,
+		r#"	<graph id="moves" edgedefault="{}">"#,
+		if exported.directed {
+			"directed"
+		} else {
+			"undirected"
+		}
+	)
+	.unwrap();
+	for (point, cell) in exported.cells.iter() {
+		write!(
+			xml,
+			r#"		<node id="{}"><data key="row">{}</data><data key="column">{}</data>"#,
+			square_name(*point),
+			point.row,
+			point.column
+		)
+		.unwrap();
+		if exported.is_target(*cell) {
+			write!(xml, r#"<data key="target">true</data>"#).unwrap();
+		}
+		if *cell == CellOption::Eliminated {
+			write!(xml, r#"<data key="eliminated">true</data>"#).unwrap();
+		}
+		writeln!(xml, "</node>").unwrap();
+	}
+	for (m, step) in exported.edges.iter() {
+		write!(
+			xml,
+			r#"		<edge source="{}" target="{}">"#,
+			square_name(m.from),
+			square_name(m.to)
+		)
+		.unwrap();
+		if let Some(step) = step {
+			write!(
+				xml,
+				r#"<data key="tour">true</data><data key="step">{}</data>"#,
+				step
+			)
+			.unwrap();
+		}
+		writeln!(xml, "</edge>").unwrap();
+	}
+	writeln!(xml, "\t</graph>\n</graphml>").unwrap();
+	xml
+}
+
+#[test]
+fn exports_move_graphs() {
+	use super::pieces::StandardPieces;
+
+	let knight: ChessPiece = StandardPieces::StandardKnight.into();
+	let mut options = BoardOptions::new(3, 3);
+	// a 3x3 knight graph is an 8 cycle around the middle
+	assert_eq!(piece_moves(&options, &knight).len(), 8);
+	assert_eq!(move_graph(&options, &knight).edge_count(), 8);
+	assert_eq!(
+		move_graph(&BoardOptions::new(8, 8), &knight).edge_count(),
+		168
+	);
+
+	options.set_point((1, 1), CellOption::Eliminated);
+	options.toggle_target((3, 3));
+	let tour: Moves = vec![
+		Move::new((1, 2).into(), (1, 2).into()),
+		Move::new((1, 2).into(), (3, 1).into()),
+		// not a knight's move
+		Move::new((3, 1).into(), (3, 2).into()),
+	]
+	.into();
+
+	let dot = to_dot(&options, &knight, &tour);
+	assert!(dot.starts_with("graph moves {"));
+	assert!(dot.contains("a1 [label=\"a1\", row=1, column=1, pos=\"0,0!\", eliminated=true"));
+	assert!(dot.contains("c3 [label=\"c3\", row=3, column=3, pos=\"2,2!\", target=true"));
+	assert!(dot.contains("b1 -- a3 [tour=true, step=1"));
+	assert!(dot.contains("a3 -- b3 [tour=true, step=2"));
+	// the moves from the eliminated cell are gone
+	assert_eq!(dot.matches(" -- ").count(), 6 + 1);
+
+	let graphml = to_graphml(&options, &knight, &tour);
+	assert!(graphml.contains(r#"edgedefault="undirected""#));
+	assert!(graphml.contains(
+		r#"<node id="c3"><data key="row">3</data><data key="column">3</data><data key="target">true</data></node>"#
+	));
+	assert!(graphml.contains(
+		r#"<edge source="b1" target="a3"><data key="tour">true</data><data key="step">1</data></edge>"#
+	));
+	assert_eq!(graphml.matches("<edge ").count(), 7);
+
+	// only moves forwards, so can't go back
+	let forwards = ChessPiece::new(vec![(1, 2), (2, 1)]);
+	let options = BoardOptions::new(3, 3);
+	assert!(to_dot(&options, &forwards, &Moves::default()).starts_with("digraph moves {"));
+	assert!(to_graphml(&options, &forwards, &Moves::default()).contains(r#"edgedefault="directed""#));
+}
@@ -13,9 +13,8 @@ use serde::{Deserialize, Serialize};
 	Hash,
 	Serialize,
 	Deserialize,
-	FromReflect,
-	Reflect,
 )]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 pub struct Move {
 	pub from: ChessPoint,
 	pub to: ChessPoint,
@@ -49,11 +48,10 @@ impl Move {
 	Default,
 	Serialize,
 	Deserialize,
-	FromReflect,
-	Reflect,
 	PartialOrd,
 	Ord,
 )]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 pub struct Moves {
 	moves: Vec<Move>,
 }
@@ -0,0 +1,241 @@
+//! Tours as algebraic notation, like `a1 c2 e1 ...`, as printed in the literature.
+//! Columns are letters from `a`, continuing `aa`, `ab`, ... past `z` for wide boards,
+//! and rows are numbers from 1
+
+use super::{pieces::ChessPiece, BoardOptions, ChessPoint, Move, Moves};
+use std::fmt::{self, Display};
+
+/// Written between squares that aren't joined by a move, like the pieces of a manual tour
+pub const SEGMENT_SEPARATOR: &str = "/";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+	/// Not of the form `a1`
+	BadSquare {
+		token: String,
+	},
+	OffBoard {
+		square: ChessPoint,
+	},
+	Unavailable {
+		square: ChessPoint,
+	},
+	/// The `step`th move (from 1) isn't one the piece can make
+	InvalidMove {
+		step: usize,
+		from: ChessPoint,
+		to: ChessPoint,
+	},
+	Empty,
+}
+
+impl Display for NotationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::BadSquare { token } => write!(f, "{:?} is not a square like a1", token),
+			Self::OffBoard { square } => {
+				write!(f, "{} is not on the board", square_name(*square))
+			}
+			Self::Unavailable { square } => {
+				write!(f, "{} is not an available cell", square_name(*square))
+			}
+			Self::InvalidMove { step, from, to } => write!(
+				f,
+				"Move {} from {} to {} is not one the piece can make",
+				step,
+				square_name(*from),
+				square_name(*to)
+			),
+			Self::Empty => write!(f, "No squares were given"),
+		}
+	}
+}
+
+impl std::error::Error for NotationError {}
+
+/// Bijective base 26, so column 27 is `aa`
+fn column_name(column: u16) -> String {
+	let mut letters = Vec::new();
+	let mut n = column as u32;
+	while n > 0 {
+		n -= 1;
+		letters.push(b'a' + (n % 26) as u8);
+		n /= 26;
+	}
+	letters.reverse();
+	String::from_utf8(letters).unwrap()
+}
+
+/// `(1, 1)` is `a1`
+pub fn square_name(point: ChessPoint) -> String {
+	format!("{}{}", column_name(point.column), point.row)
+}
+
+/// Inverse of [square_name], ignoring case
+pub fn parse_square(square: &str) -> Option<ChessPoint> {
+	let digits = square.find(|c: char| c.is_ascii_digit())?;
+	let (letters, row) = square.split_at(digits);
+	if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+		return None;
+	}
+	let column = letters.chars().try_fold(0u16, |column, c| {
+		let digit = (c.to_ascii_lowercase() as u8 - b'a') as u16 + 1;
+		column.checked_mul(26)?.checked_add(digit)
+	})?;
+	let row: u16 = row.parse().ok()?;
+	(row > 0).then_some(ChessPoint::new(row, column))
+}
+
+/// Squares in the order they are visited, separated by spaces.
+/// Self moves are skipped, and moves that don't start where the last one ended
+/// begin a new segment after [SEGMENT_SEPARATOR]
+pub fn write_tour(moves: &Moves) -> String {
+	let mut squares: Vec<String> = Vec::new();
+	let mut current: Option<ChessPoint> = None;
+	for m in moves.iter() {
+		if current != Some(m.from) {
+			if current.is_some() {
+				squares.push(SEGMENT_SEPARATOR.to_string());
+			}
+			squares.push(square_name(m.from));
+		}
+		if m.from != m.to {
+			squares.push(square_name(m.to));
+		}
+		current = Some(m.to);
+	}
+	squares.join(" ")
+}
+
+/// Squares of each segment of `text`, leniently split so tours copied from books
+/// like `1.Na1 2.Nc2, e1-...` also parse
+fn parse_segments(text: &str) -> Result<Vec<Vec<ChessPoint>>, NotationError> {
+	let text = text.replace(SEGMENT_SEPARATOR, &format!(" {} ", SEGMENT_SEPARATOR));
+	let mut segments = vec![Vec::new()];
+	for token in text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '-')) {
+		// move numbers like `12.` or `12)`
+		let token = token.trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ')'));
+		if token.is_empty() {
+			continue;
+		}
+		if token == SEGMENT_SEPARATOR {
+			segments.push(Vec::new());
+			continue;
+		}
+		// a piece letter, like the N of Nc3
+		let mut chars = token.chars();
+		let square = match (chars.next(), chars.next()) {
+			(Some(piece), Some(next)) if piece.is_ascii_uppercase() && next.is_ascii_lowercase() => {
+				&token[1..]
+			}
+			_ => token,
+		};
+		let point = parse_square(square).ok_or_else(|| NotationError::BadSquare {
+			token: token.to_string(),
+		})?;
+		segments.last_mut().unwrap().push(point);
+	}
+	segments.retain(|segment| !segment.is_empty());
+	Ok(segments)
+}
+
+/// Reads a tour written by [write_tour] or copied from elsewhere,
+/// checking every square is available and every step is a move `piece` can make.
+/// The jump between segments isn't checked
+pub fn read_tour(
+	text: &str,
+	piece: &ChessPiece,
+	options: &BoardOptions,
+) -> Result<Moves, NotationError> {
+	let segments = parse_segments(text)?;
+	if segments.is_empty() {
+		return Err(NotationError::Empty);
+	}
+
+	let mut moves = Moves::default();
+	for segment in segments {
+		for square in segment.iter() {
+			if !options.validate_point(square) {
+				return Err(NotationError::OffBoard { square: *square });
+			}
+			if !options.is_available(square) {
+				return Err(NotationError::Unavailable { square: *square });
+			}
+		}
+		if let [only] = segment[..] {
+			moves.push(Move::new(only, only));
+		}
+		for step in segment.windows(2) {
+			let (from, to) = (step[0], step[1]);
+			if !piece.is_valid_move(from, to, options) {
+				return Err(NotationError::InvalidMove {
+					step: moves.len() + 1,
+					from,
+					to,
+				});
+			}
+			moves.push(Move::new(from, to));
+		}
+	}
+	Ok(moves)
+}
+
+#[test]
+fn tours_survive_notation() {
+	assert_eq!(square_name(ChessPoint::new(3, 1)), "a3");
+	assert_eq!(square_name(ChessPoint::new(12, 26)), "z12");
+	assert_eq!(square_name(ChessPoint::new(1, 27)), "aa1");
+	assert_eq!(square_name(ChessPoint::new(1, 53)), "ba1");
+	for column in 1..=800 {
+		let point = ChessPoint::new(7, column);
+		assert_eq!(parse_square(&square_name(point)), Some(point));
+	}
+	assert_eq!(parse_square("C2"), Some(ChessPoint::new(2, 3)));
+	assert_eq!(parse_square("c0"), None);
+	assert_eq!(parse_square("2c"), None);
+
+	let knight = ChessPiece::default();
+	let options = BoardOptions::new(6, 6);
+	let tour = super::algs::construct_closed_tour(6, 6, ChessPoint::new(1, 1)).unwrap();
+	let text = write_tour(&tour);
+	assert!(text.starts_with("a1 "));
+	let read = read_tour(&text, &knight, &options).unwrap();
+	let without_self_moves: Vec<Move> = tour.iter().filter(|m| m.from != m.to).copied().collect();
+	assert_eq!(*read, without_self_moves);
+
+	let options = BoardOptions::new(8, 8);
+	let copied = "1.Na1 2.Nb3, 3.Nc1 - 4.Ne2 / h8; g6";
+	assert_eq!(
+		read_tour(copied, &knight, &options).unwrap().to_vec(),
+		vec![
+			Move::new((1, 1).into(), (3, 2).into()),
+			Move::new((3, 2).into(), (1, 3).into()),
+			Move::new((1, 3).into(), (2, 5).into()),
+			Move::new((8, 8).into(), (6, 7).into()),
+		]
+	);
+	assert_eq!(
+		write_tour(&read_tour(copied, &knight, &options).unwrap()),
+		"a1 b3 c1 e2 / h8 g6"
+	);
+
+	assert_eq!(
+		read_tour("a1 b3 c4", &knight, &options),
+		Err(NotationError::InvalidMove {
+			step: 2,
+			from: ChessPoint::new(3, 2),
+			to: ChessPoint::new(4, 3),
+		})
+	);
+	assert_eq!(
+		read_tour("a1 i2", &knight, &options),
+		Err(NotationError::OffBoard {
+			square: ChessPoint::new(2, 9)
+		})
+	);
+	assert!(matches!(
+		read_tour("a1 xyz", &knight, &options),
+		Err(NotationError::BadSquare { .. })
+	));
+	assert_eq!(read_tour(" ", &knight, &options), Err(NotationError::Empty));
+}
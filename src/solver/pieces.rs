@@ -1,33 +1,115 @@
+#[cfg(feature = "gui")]
 use bevy::reflect::{FromReflect, Reflect};
-use bevy_egui::egui::{self, RichText, Ui};
-
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use strum::{EnumIs, EnumIter};
 
-use crate::{board::StateInvalidated, ChessPoint};
+use crate::solver::{BoardOptions, ChessPoint};
 
-/// Holds info on valid moves
+/// Holds info on valid moves.
+/// Moves don't have to be symmetric, a piece that can only move one way is fine
 #[derive(Hash, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 pub struct ChessPiece {
+	/// Leaps, that jump straight to the offset over anything in the way
 	valid_moves: Vec<(i16, i16)>,
+	/// Rides, that slide any number of steps along the offset
+	/// until blocked by a cell that isn't available
+	#[serde(default)]
+	rides: Vec<(i16, i16)>,
 }
 
 impl ChessPiece {
+	/// A leaper
 	pub fn new(moves: Vec<(i16, i16)>) -> Self {
-		Self { valid_moves: moves }
+		Self {
+			valid_moves: moves,
+			rides: Vec::new(),
+		}
+	}
+
+	pub fn rider(steps: Vec<(i16, i16)>) -> Self {
+		Self {
+			valid_moves: Vec::new(),
+			rides: steps,
+		}
 	}
 
+	/// Moves any way one of `pieces` can
+	pub fn compound(pieces: impl IntoIterator<Item = ChessPiece>) -> Self {
+		let mut compound = Self::new(Vec::new());
+		for piece in pieces {
+			for d in piece.valid_moves {
+				if !compound.valid_moves.contains(&d) {
+					compound.valid_moves.push(d);
+				}
+			}
+			for d in piece.rides {
+				if !compound.rides.contains(&d) {
+					compound.rides.push(d);
+				}
+			}
+		}
+		compound
+	}
+
+	/// The leaps, see [Self::rides]
 	pub fn relative_moves(&self) -> &Vec<(i16, i16)> {
 		&self.valid_moves
 	}
 
-	pub fn is_valid_move(&self, from: ChessPoint, to: ChessPoint) -> bool {
-		// checks self.relative_moves
-		let dx = to.column as i16 - from.column as i16;
-		let dy = to.row as i16 - from.row as i16;
-		self.relative_moves().contains(&(dx, dy))
+	pub fn rides(&self) -> &Vec<(i16, i16)> {
+		&self.rides
+	}
+
+	/// Whether every move can be undone by another move
+	pub fn is_symmetric(&self) -> bool {
+		let closed = |moves: &Vec<(i16, i16)>| moves.iter().all(|(dx, dy)| moves.contains(&(-dx, -dy)));
+		closed(&self.valid_moves) && closed(&self.rides)
+	}
+
+	/// Whether every move lands on the other colour of a (flat) chess board
+	pub fn always_changes_colour(&self) -> bool {
+		self.rides.is_empty() && self.valid_moves.iter().all(|(dx, dy)| (dx + dy) % 2 != 0)
 	}
 
+	/// Available cells this piece can move to from `from`, wrapping around joined edges.
+	/// Rides are blocked by unavailable cells, but not by cells already visited
+	pub fn destinations(&self, from: ChessPoint, board: &BoardOptions) -> Vec<ChessPoint> {
+		let mut destinations: Vec<ChessPoint> = self
+			.valid_moves
+			.iter()
+			.filter_map(|d| board.displace(from, d))
+			.filter(|p| *p != from && board.is_available(p))
+			.collect();
+
+		// at most one lap around a torus
+		let max_steps = board.width() as usize * board.height() as usize;
+		for (dx, dy) in self.rides.iter() {
+			for steps in (1..).take(max_steps) {
+				let (Some(x), Some(y)) = (dx.checked_mul(steps), dy.checked_mul(steps)) else {
+					break;
+				};
+				// displacing the whole way at once keeps rides straight across a twisted edge
+				match board.displace(from, &(x, y)) {
+					Some(p) if p != from && board.is_available(&p) => destinations.push(p),
+					_ => break,
+				}
+			}
+		}
+
+		destinations.sort();
+		destinations.dedup();
+		destinations
+	}
+
+	/// Whether one of this piece's moves goes from `from` to `to` on `board`,
+	/// including around its joined edges
+	pub fn is_valid_move(&self, from: ChessPoint, to: ChessPoint, board: &BoardOptions) -> bool {
+		self.destinations(from, board).contains(&to)
+	}
+
+	/// Leaps only, on a flat unbounded board
 	pub fn get_unchecked_relative_points(&self, start: ChessPoint) -> Vec<ChessPoint> {
 		self
 			.relative_moves()
@@ -57,6 +139,60 @@ fn test_get_unchecked_relative_points() {
 	assert!(points.contains(&ChessPoint::new(6, 2)));
 }
 
+#[test]
+fn riders_are_blocked_and_directional_pieces_tour() {
+	use crate::solver::algs::{count_tours, tours, Algorithm, OwnedComputeInput, TourKind};
+
+	let mut options = BoardOptions::new(5, 5);
+	options.rm((1, 4));
+	let rook: ChessPiece = StandardPieces::Rook.into();
+	let from = ChessPoint::new(1, 1);
+	let mut expected: Vec<_> = [(2, 1), (3, 1), (4, 1), (5, 1), (1, 2), (1, 3)]
+		.into_iter()
+		.map(|(row, column)| ChessPoint::new(row, column))
+		.collect();
+	expected.sort();
+	assert_eq!(rook.destinations(from, &options), expected);
+
+	// blocked by the cell it would pass over, unlike a knight
+	let nightrider: ChessPiece = StandardPieces::Nightrider.into();
+	options.rm((3, 2));
+	assert!(!nightrider.is_valid_move(from, ChessPoint::new(5, 3), &options));
+	assert!(nightrider.is_valid_move(from, ChessPoint::new(2, 3), &options));
+	assert!(nightrider.is_valid_move(from, ChessPoint::new(3, 5), &options));
+
+	// huge rides stop instead of overflowing, even when looking for wrapped moves
+	let huge = ChessPiece::rider(vec![(1000, 1)]);
+	let mut torus = BoardOptions::new(40, 40);
+	torus.set_topology(crate::solver::Topology::Torus);
+	let to = huge.destinations(from, &torus)[0];
+	assert!(torus.wrapped_move(&huge, from, to).is_some());
+
+	// can only ever move up, right, or back diagonally
+	let directional = ChessPiece::new(vec![(1, 0), (0, 1), (-1, -1)]);
+	assert!(!directional.is_symmetric());
+	let input = OwnedComputeInput {
+		alg: Algorithm::default(),
+		safety_cap: None,
+		time_budget: None,
+		pruning: Default::default(),
+		start: ChessPoint::new(1, 1),
+		board_options: BoardOptions::new(3, 3),
+		piece: directional.clone(),
+	};
+	let handle = Default::default();
+	assert!(count_tours(&input, TourKind::Open, &handle).found > 0);
+	for tour in tours(input, TourKind::Open) {
+		for m in tour.iter().take(8) {
+			assert!(
+				directional.is_valid_move(m.from, m.to, &BoardOptions::new(3, 3)),
+				"{}",
+				m
+			);
+		}
+	}
+}
+
 /// Collection of standard sets of moves
 #[derive(
 	Default,
@@ -69,9 +205,8 @@ fn test_get_unchecked_relative_points() {
 	EnumIs,
 	EnumIter,
 	strum::Display,
-	Reflect,
-	FromReflect,
 )]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
 pub enum StandardPieces {
 	/// Same as [Pieces::ABKnight(1, 2)]
 	#[strum(serialize = "Standard Knight")]
@@ -80,6 +215,132 @@ pub enum StandardPieces {
 
 	#[strum(serialize = "AB Knight")]
 	ABKnight(i8, i8),
+
+	/// (1, 3) leaper
+	Camel,
+	/// (2, 3) leaper
+	Zebra,
+	/// (1, 4) leaper
+	Giraffe,
+	/// (0, 1) leaper
+	Wazir,
+	/// (1, 1) leaper
+	Ferz,
+	/// Wazir and Ferz
+	King,
+
+	/// Rides along knight moves
+	Nightrider,
+	Rook,
+	Bishop,
+	/// Rook and Bishop
+	Queen,
+
+	/// Queen and Knight
+	Amazon,
+	/// Bishop and Knight
+	Archbishop,
+	/// Rook and Knight
+	Chancellor,
+}
+
+impl StandardPieces {
+	/// Every named fairy piece, in the order shown in the UI
+	pub const FAIRY: [Self; 13] = [
+		Self::Camel,
+		Self::Zebra,
+		Self::Giraffe,
+		Self::Wazir,
+		Self::Ferz,
+		Self::King,
+		Self::Nightrider,
+		Self::Rook,
+		Self::Bishop,
+		Self::Queen,
+		Self::Amazon,
+		Self::Archbishop,
+		Self::Chancellor,
+	];
+
+	pub fn get_description(&self) -> &'static str {
+		match self {
+			Self::StandardKnight => "Leaps 1 square one way and 2 squares the other",
+			Self::ABKnight(..) => "Leaps A squares one way and B squares the other",
+			Self::Camel => "Leaps 1 square one way and 3 squares the other",
+			Self::Zebra => "Leaps 2 squares one way and 3 squares the other",
+			Self::Giraffe => "Leaps 1 square one way and 4 squares the other",
+			Self::Wazir => "Steps 1 square orthogonally",
+			Self::Ferz => "Steps 1 square diagonally",
+			Self::King => "Steps 1 square in any direction",
+			Self::Nightrider => {
+				"Repeats knight moves in a straight line, until blocked by a disabled cell"
+			}
+			Self::Rook => "Slides orthogonally, until blocked by a disabled cell",
+			Self::Bishop => "Slides diagonally, until blocked by a disabled cell",
+			Self::Queen => "Slides in any direction, until blocked by a disabled cell",
+			Self::Amazon => "Moves like a Queen or a Knight",
+			Self::Archbishop => "Moves like a Bishop or a Knight",
+			Self::Chancellor => "Moves like a Rook or a Knight",
+		}
+	}
+
+	/// Short name parsed by [FromStr], like `knight`, `camel` or `ab:2,3`
+	pub fn code(&self) -> String {
+		match self {
+			Self::StandardKnight => "knight".to_string(),
+			Self::ABKnight(a, b) => format!("ab:{},{}", a, b),
+			piece => piece.to_string().to_lowercase(),
+		}
+	}
+}
+
+impl FromStr for StandardPieces {
+	type Err = String;
+
+	/// Case insensitive, see [Self::code]
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.eq_ignore_ascii_case("knight") {
+			return Ok(Self::StandardKnight);
+		}
+		if let Some(piece) = Self::FAIRY
+			.into_iter()
+			.find(|piece| piece.to_string().eq_ignore_ascii_case(s))
+		{
+			return Ok(piece);
+		}
+		let (a, b) = s
+			.strip_prefix("ab:")
+			.and_then(|ab| ab.split_once(','))
+			.ok_or_else(|| {
+				format!(
+					"Expected `knight`, a fairy piece like `camel` or `nightrider`, or `ab:A,B`, got {:?}",
+					s
+				)
+			})?;
+		let a = a.trim().parse().map_err(|e| format!("Bad A: {}", e))?;
+		let b = b.trim().parse().map_err(|e| format!("Bad B: {}", e))?;
+		Ok(Self::ABKnight(a, b))
+	}
+}
+
+/// All 8 combinations of signs and orders of `(a, b)`, without duplicates
+fn symmetric(a: i16, b: i16) -> Vec<(i16, i16)> {
+	let mut moves = Vec::new();
+	for d in [
+		(a, b),
+		(-a, b),
+		(a, -b),
+		(-a, -b),
+		(b, a),
+		(-b, a),
+		(b, -a),
+		(-b, -a),
+	] {
+		if !moves.contains(&d) {
+			moves.push(d);
+		}
+	}
+	moves
 }
 
 impl Default for ChessPiece {
@@ -88,10 +349,12 @@ impl Default for ChessPiece {
 	}
 }
 
-impl From<StandardPieces> for Vec<(i16, i16)> {
+impl From<StandardPieces> for ChessPiece {
 	fn from(value: StandardPieces) -> Self {
+		let leaper = |a, b| ChessPiece::new(symmetric(a, b));
+		let rider = |a, b| ChessPiece::rider(symmetric(a, b));
 		match value {
-			StandardPieces::StandardKnight => vec![
+			StandardPieces::StandardKnight => ChessPiece::new(vec![
 				(2, 1),
 				(1, 2),
 				(-1, 2),
@@ -100,93 +363,237 @@ impl From<StandardPieces> for Vec<(i16, i16)> {
 				(-1, -2),
 				(1, -2),
 				(2, -1),
-			],
-			StandardPieces::ABKnight(a, b) => {
-				let a = a as i16;
-				let b = b as i16;
-				vec![
-					(a, b),
-					(-a, b),
-					(a, -b),
-					(-a, -b),
-					(b, a),
-					(-b, a),
-					(b, -a),
-					(-b, -a),
-				]
+			]),
+			StandardPieces::ABKnight(a, b) => leaper(a as i16, b as i16),
+			StandardPieces::Camel => leaper(1, 3),
+			StandardPieces::Zebra => leaper(2, 3),
+			StandardPieces::Giraffe => leaper(1, 4),
+			StandardPieces::Wazir => leaper(0, 1),
+			StandardPieces::Ferz => leaper(1, 1),
+			StandardPieces::King => ChessPiece::compound([leaper(0, 1), leaper(1, 1)]),
+			StandardPieces::Nightrider => rider(1, 2),
+			StandardPieces::Rook => rider(0, 1),
+			StandardPieces::Bishop => rider(1, 1),
+			StandardPieces::Queen => ChessPiece::compound([rider(0, 1), rider(1, 1)]),
+			StandardPieces::Amazon => ChessPiece::compound([
+				rider(0, 1),
+				rider(1, 1),
+				StandardPieces::StandardKnight.into(),
+			]),
+			StandardPieces::Archbishop => {
+				ChessPiece::compound([rider(1, 1), StandardPieces::StandardKnight.into()])
+			}
+			StandardPieces::Chancellor => {
+				ChessPiece::compound([rider(0, 1), StandardPieces::StandardKnight.into()])
 			}
 		}
 	}
 }
 
-impl From<StandardPieces> for ChessPiece {
+/// A piece made in the piece editor, by picking the offsets it can leap to
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
+pub struct CustomPiece {
+	pub name: String,
+	pub piece: ChessPiece,
+}
+
+/// The piece tours are found for
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
+pub enum Piece {
+	Standard(StandardPieces),
+	Custom(CustomPiece),
+}
+
+impl Piece {
+	pub fn as_custom(&self) -> Option<&CustomPiece> {
+		match self {
+			Self::Standard(_) => None,
+			Self::Custom(custom) => Some(custom),
+		}
+	}
+}
+
+impl Default for Piece {
+	fn default() -> Self {
+		Self::Standard(StandardPieces::default())
+	}
+}
+
+impl From<StandardPieces> for Piece {
 	fn from(value: StandardPieces) -> Self {
-		ChessPiece::new(value.into())
+		Self::Standard(value)
 	}
 }
 
-impl StandardPieces {
-	const MAX_AB: i8 = 5;
-	const MIN_AB: i8 = 0;
-
-	pub fn ui(&mut self, ui: &mut Ui) -> StateInvalidated {
-		let mut invalidate = StateInvalidated::Valid;
-
-		if ui
-			.button(RichText::new("Standard Knight").color({
-				if self.is_standard_knight() {
-					egui::Color32::GREEN
-				} else {
-					egui::Color32::GRAY
-				}
-			}))
-			.clicked()
-		{
-			*self = StandardPieces::StandardKnight;
-			invalidate = StateInvalidated::Invalidated;
-		}
-		if ui
-			.button(RichText::new("AB Knight").color({
-				if self.is_ab_knight() {
-					egui::Color32::GREEN
-				} else {
-					egui::Color32::GRAY
-				}
-			}))
-			.clicked()
-		{
-			*self = StandardPieces::ABKnight(2, 1);
+impl From<&Piece> for ChessPiece {
+	fn from(value: &Piece) -> Self {
+		match value {
+			Piece::Standard(piece) => (*piece).into(),
+			Piece::Custom(custom) => custom.piece.clone(),
 		}
+	}
+}
 
-		if let StandardPieces::ABKnight(a, b) = self {
-			ui.add(
-				egui::Slider::from_get_set((Self::MIN_AB as f64)..=(Self::MAX_AB as f64), |val| {
-					if let Some(val) = val {
-						*a = val as i8;
-						invalidate = StateInvalidated::Invalidated;
+impl From<Piece> for ChessPiece {
+	fn from(value: Piece) -> Self {
+		(&value).into()
+	}
+}
+
+impl std::fmt::Display for Piece {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Standard(piece) => write!(f, "{}", piece),
+			Self::Custom(custom) => write!(f, "{}", custom.name),
+		}
+	}
+}
+
+/// `d`, and the offsets the piece editor's mirror and rotation helpers copy it to
+pub fn symmetric_images(d: (i16, i16), mirror: bool, rotate: bool) -> Vec<(i16, i16)> {
+	let mut images = vec![d];
+	if rotate {
+		for _ in 0..3 {
+			let (dx, dy) = images[images.len() - 1];
+			images.push((-dy, dx));
+		}
+	}
+	if mirror {
+		for (dx, dy) in images.clone() {
+			images.extend([(-dx, dy), (dx, -dy), (-dx, -dy)]);
+		}
+	}
+	images.sort();
+	images.dedup();
+	images
+}
+
+#[test]
+fn symmetry_helpers_fill_in_standard_pieces() {
+	let mut images = symmetric_images((1, 2), true, true);
+	let mut knight = ChessPiece::default().relative_moves().clone();
+	images.sort();
+	knight.sort();
+	assert_eq!(images, knight);
+
+	assert_eq!(symmetric_images((0, 1), false, true).len(), 4);
+	assert_eq!(
+		symmetric_images((1, 2), true, false),
+		vec![(-1, -2), (-1, 2), (1, -2), (1, 2)]
+	);
+	assert_eq!(symmetric_images((1, 2), false, false), vec![(1, 2)]);
+}
+
+#[cfg(feature = "gui")]
+mod ui {
+	use super::*;
+	use crate::board::StateInvalidated;
+	use bevy_egui::egui::{self, RichText, Ui};
+
+	impl StandardPieces {
+		const MAX_AB: i8 = 5;
+		const MIN_AB: i8 = 0;
+
+		/// `is_selected` is false while a custom piece is used instead
+		pub fn ui(&mut self, ui: &mut Ui, is_selected: bool) -> StateInvalidated {
+			let mut invalidate = StateInvalidated::Valid;
+
+			if ui
+				.button(RichText::new("Standard Knight").color({
+					if is_selected && self.is_standard_knight() {
+						egui::Color32::GREEN
+					} else {
+						egui::Color32::GRAY
 					}
-					*a as f64
-				})
-				.text("A"),
-			);
+				}))
+				.clicked()
+			{
+				*self = StandardPieces::StandardKnight;
+				invalidate = StateInvalidated::Invalidated;
+			}
+			if ui
+				.button(RichText::new("AB Knight").color({
+					if is_selected && self.is_ab_knight() {
+						egui::Color32::GREEN
+					} else {
+						egui::Color32::GRAY
+					}
+				}))
+				.clicked()
+			{
+				*self = StandardPieces::ABKnight(2, 1);
+			}
 
-			ui.add(
-				egui::Slider::from_get_set((Self::MIN_AB as f64)..=(Self::MAX_AB as f64), |val| {
-					if let Some(val) = val {
-						*b = val as i8;
+			ui.horizontal_wrapped(|ui| {
+				for piece in Self::FAIRY {
+					if ui
+						.button(RichText::new(piece.to_string()).color({
+							if is_selected && *self == piece {
+								egui::Color32::GREEN
+							} else {
+								egui::Color32::GRAY
+							}
+						}))
+						.on_hover_text(piece.get_description())
+						.clicked()
+					{
+						*self = piece;
 						invalidate = StateInvalidated::Invalidated;
 					}
-					*b as f64
-				})
-				.text("B"),
-			);
+				}
+			});
+
+			if let StandardPieces::ABKnight(a, b) = self {
+				ui.add(
+					egui::Slider::from_get_set((Self::MIN_AB as f64)..=(Self::MAX_AB as f64), |val| {
+						if let Some(val) = val {
+							*a = val as i8;
+							invalidate = StateInvalidated::Invalidated;
+						}
+						*a as f64
+					})
+					.text("A"),
+				);
 
-			ui.label("An 'AB Knight' refers to a piece that must move A squares in one direction, and B squares in any perpendicular direction.
+				ui.add(
+					egui::Slider::from_get_set((Self::MIN_AB as f64)..=(Self::MAX_AB as f64), |val| {
+						if let Some(val) = val {
+							*b = val as i8;
+							invalidate = StateInvalidated::Invalidated;
+						}
+						*b as f64
+					})
+					.text("B"),
+				);
+
+				ui.label("An 'AB Knight' refers to a piece that must move A squares in one direction, and B squares in any perpendicular direction.
 This makes the Standard knight equivalent to an AB Knight with A=1 and B=2, or A=2 and B=1.
-And, a bishop is equivalen to a [1, 1] AB knight (diagonals only).
+And, a Ferz is equivalent to a [1, 1] AB knight (one diagonal step only).
 Trying playing with a [1, 0] knight!");
+			}
+
+			invalidate
 		}
+	}
 
-		invalidate
+	impl Piece {
+		pub fn ui(&mut self, ui: &mut Ui) -> StateInvalidated {
+			let (mut standard, is_selected) = match self {
+				Piece::Standard(piece) => (*piece, true),
+				Piece::Custom(custom) => {
+					ui.label(format!("Using the custom piece '{}'", custom.name));
+					(StandardPieces::default(), false)
+				}
+			};
+			let before = standard;
+			let invalidate = standard.ui(ui, is_selected);
+			if standard != before || !matches!(invalidate, StateInvalidated::Valid) {
+				*self = Piece::Standard(standard);
+				return StateInvalidated::Invalidated;
+			}
+			invalidate
+		}
 	}
 }
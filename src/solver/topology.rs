@@ -0,0 +1,129 @@
+#[cfg(feature = "gui")]
+use bevy::reflect::{FromReflect, Reflect};
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::ChessPoint;
+
+/// How the edges of a board are joined, see [super::BoardOptions::displace]
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	Hash,
+	PartialOrd,
+	Ord,
+	Serialize,
+	Deserialize,
+	EnumIter,
+	strum::Display,
+)]
+#[cfg_attr(feature = "gui", derive(Reflect, FromReflect))]
+pub enum Topology {
+	/// Hard edges on every side
+	#[default]
+	Flat,
+	/// The left and right edges are joined
+	Cylinder,
+	/// The left and right edges are joined, and so are the top and bottom
+	Torus,
+	/// The left and right edges are joined with a half twist,
+	/// so moving off one side comes back on the other side upside down
+	#[strum(serialize = "Möbius strip")]
+	Mobius,
+}
+
+/// The edges crossed to wrap a point back onto the board,
+/// as the transformation that moved it there
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Seam {
+	pub rows: i32,
+	pub columns: i32,
+	/// Upside down, only across the twisted edge of a [Topology::Mobius] strip
+	pub flipped: bool,
+}
+
+impl Seam {
+	pub fn is_crossed(&self) -> bool {
+		*self != Self::default()
+	}
+
+	/// Moves `(row, column)` the same way.
+	/// Applied to the start of a wrapping move, gives where it comes from on the other side
+	pub fn apply(&self, (row, column): (i32, i32), height: u16) -> (i32, i32) {
+		let row = if self.flipped {
+			height as i32 + 1 - row
+		} else {
+			row
+		};
+		(row + self.rows, column + self.columns)
+	}
+}
+
+impl Topology {
+	/// Brings `(row, column)` back onto a `width` x `height` board if it fell off a joined edge
+	pub fn wrap(self, row: i32, column: i32, width: u16, height: u16) -> Option<(ChessPoint, Seam)> {
+		let (width, height_i) = (width as i32, height as i32);
+		let (wrap_rows, wrap_columns) = match self {
+			Self::Flat => (false, false),
+			Self::Cylinder | Self::Mobius => (false, true),
+			Self::Torus => (true, true),
+		};
+
+		let mut seam = Seam::default();
+		if !(1..=height_i).contains(&row) {
+			if !wrap_rows {
+				return None;
+			}
+			seam.rows = (row - 1).rem_euclid(height_i) + 1 - row;
+		}
+		if !(1..=width).contains(&column) {
+			if !wrap_columns {
+				return None;
+			}
+			seam.columns = (column - 1).rem_euclid(width) + 1 - column;
+			// crossing the twist twice puts it back the right way up
+			seam.flipped = self == Self::Mobius && (column - 1).div_euclid(width) % 2 != 0;
+		}
+
+		let (row, column) = seam.apply((row, column), height);
+		Some((ChessPoint::new(row as u16, column as u16), seam))
+	}
+
+	/// Whether cells keep their chess board colour across the joined edges,
+	/// so pieces that always change colour on a flat board still do
+	pub fn preserves_colours(self, width: u16, height: u16) -> bool {
+		match self {
+			Self::Flat => true,
+			Self::Cylinder => width.is_multiple_of(2),
+			Self::Torus => width.is_multiple_of(2) && height.is_multiple_of(2),
+			// the twist also turns row r into row height + 1 - r
+			Self::Mobius => (width + height) % 2 == 1,
+		}
+	}
+}
+
+#[test]
+fn wraps_across_joined_edges() {
+	let (width, height) = (8, 5);
+	assert_eq!(Topology::Flat.wrap(2, 9, width, height), None);
+	assert_eq!(Topology::Cylinder.wrap(6, 1, width, height), None);
+
+	let (p, seam) = Topology::Cylinder.wrap(2, 9, width, height).unwrap();
+	assert_eq!(p, ChessPoint::new(2, 1));
+	assert_eq!(seam.columns, -8);
+
+	let (p, _) = Topology::Torus.wrap(0, -1, width, height).unwrap();
+	assert_eq!(p, ChessPoint::new(5, 7));
+
+	// upside down on the other side
+	let (p, seam) = Topology::Mobius.wrap(2, 10, width, height).unwrap();
+	assert_eq!(p, ChessPoint::new(4, 2));
+	assert!(seam.flipped);
+	assert_eq!(seam.apply((2, 10), height), (4, 2));
+	let (p, _) = Topology::Mobius.wrap(2, 18, width, height).unwrap();
+	assert_eq!(p, ChessPoint::new(2, 2));
+}
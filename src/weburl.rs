@@ -1,47 +1,433 @@
+use anyhow::Context;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+#[cfg(feature = "gui")]
 use bevy::prelude::Resource;
 use derive_more::{Constructor, Into};
+use serde::{Deserialize, Serialize};
+
+use crate::solver::{
+	algs::Algorithm,
+	pieces::{ChessPiece, CustomPiece, Piece, StandardPieces},
+	BoardOptions, CellOption, ChessPoint, Move, Topology,
+};
 
 const URL: &str = "https://caleb-msrc-q11.netlify.app/";
 
-#[derive(Resource, Clone, Constructor, Into)]
+#[derive(Clone, Constructor, Into)]
+#[cfg_attr(feature = "gui", derive(Resource))]
 pub struct InitialLoadedID(serde_json::Value);
 
+/// Everything needed to show a board without the database, small enough to share as a link
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Resource))]
+pub struct SharedBoard {
+	pub board_options: BoardOptions,
+	pub piece: Piece,
+	pub start: Option<ChessPoint>,
+	/// Displayed moves, with the index of their colour
+	pub moves: Vec<(Move, u8)>,
+}
+
+/// What a link points to
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlData {
+	/// A save in the database
+	Id(String),
+	Board(SharedBoard),
+}
+
+/// Query parameters of a link, see [create_url_with_board].
+/// Lists are packed into bytes then base64 encoded, which keeps them URL safe
+#[derive(Serialize, Deserialize, Default)]
+struct Query {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	id: Option<String>,
+
+	/// Version of the board encoding, bumped whenever it changes
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	v: Option<u8>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	w: Option<u16>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	h: Option<u16>,
+	/// 2 bits per cell, row by row from the bottom left, see [cell_bits]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	c: Option<String>,
+	/// Missing for [Topology::Flat]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	t: Option<Topology>,
+	/// Recommended moves
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	r: Option<String>,
+
+	/// [StandardPieces::code], or `custom` for a piece from the piece editor.
+	/// Missing for the standard knight
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	p: Option<String>,
+	/// Name of a custom piece
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pn: Option<String>,
+	/// Leaps of a custom piece
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pl: Option<String>,
+	/// Rides of a custom piece
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pr: Option<String>,
+
+	/// `row,column`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	s: Option<String>,
+	/// Moves, each followed by its colour
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	m: Option<String>,
+}
+
+const BOARD_VERSION: u8 = 1;
+
 #[cfg(target_arch = "wasm32")]
-pub fn get_url_id() -> Option<String> {
+pub fn get_url_data() -> Option<UrlData> {
 	let window = web_sys::window().expect("To be able to get window");
 	let location = window.location();
 	let full_href = location.href().expect("Website has no href?");
 
-	extract_url(full_href)
+	extract_url(&full_href)
 }
 
-#[allow(dead_code)]
-fn extract_url(full_href: String) -> Option<String> {
+/// Reads a link made by [create_url_with_id] or [create_url_with_board].
+/// Returns [None] if it has neither, or the board can't be decoded
+pub fn extract_url(full_href: &str) -> Option<UrlData> {
 	// id=jhlfjsdh&junk=hjklhlkj
-	let query_params = full_href.split('?').nth(1)?;
+	let query_params = full_href.split_once('?')?.1;
+	let query_params = query_params.split('#').next()?;
+	let query: Query = match serde_qs::from_str(query_params) {
+		Ok(query) => query,
+		Err(err) => {
+			tracing::warn!("Couldn't read link {:?}: {}", full_href, err);
+			return None;
+		}
+	};
 
-	// id=hjklh
-	let id_param = query_params
-		.split('&')
-		.next()?
-		.split('=')
-		.collect::<Vec<_>>();
-
-	if id_param.first()? != &"id" {
-		return None;
+	if query.w.is_some() {
+		match decode_board(query) {
+			Ok(board) => Some(UrlData::Board(board)),
+			Err(err) => {
+				tracing::warn!("Couldn't decode the board in {:?}: {:#}", full_href, err);
+				None
+			}
+		}
+	} else {
+		query.id.map(UrlData::Id)
 	}
-	let id = id_param.get(1)?;
-
-	Some(id.to_string())
 }
 
 pub fn create_url_with_id(id: String) -> String {
 	format!("{}?id={}", URL, id)
 }
 
+/// Link that reproduces `board` without the database
+pub fn create_url_with_board(board: &SharedBoard) -> String {
+	let query = serde_qs::to_string(&encode_board(board)).expect("Cannot serialise board");
+	format!("{}?{}", URL, query)
+}
+
+fn encode_board(board: &SharedBoard) -> Query {
+	let options = &board.board_options;
+	let (width, height) = options.dimensions();
+	let cell = |p: ChessPoint| (p.row as u32 - 1) * width as u32 + (p.column as u32 - 1);
+
+	let mut cells = vec![0; (width as usize * height as usize).div_ceil(4)];
+	for (i, p) in all_points(width, height).enumerate() {
+		cells[i / 4] |= cell_bits(options.get(&p).unwrap()) << (i % 4 * 2);
+	}
+
+	let mut recommended = Vec::new();
+	for m in options.recommended_moves().iter() {
+		write_varint(&mut recommended, cell(m.from));
+		write_varint(&mut recommended, cell(m.to));
+	}
+	let mut moves = Vec::new();
+	for (m, colour) in board.moves.iter() {
+		write_varint(&mut moves, cell(m.from));
+		write_varint(&mut moves, cell(m.to));
+		moves.push(*colour);
+	}
+
+	let mut query = Query {
+		v: Some(BOARD_VERSION),
+		w: Some(width),
+		h: Some(height),
+		c: Some(URL_SAFE_NO_PAD.encode(cells)),
+		t: (options.topology() != Topology::Flat).then_some(options.topology()),
+		r: (!recommended.is_empty()).then(|| URL_SAFE_NO_PAD.encode(recommended)),
+		s: board.start.map(|p| format!("{},{}", p.row, p.column)),
+		m: (!moves.is_empty()).then(|| URL_SAFE_NO_PAD.encode(moves)),
+		..Default::default()
+	};
+	match &board.piece {
+		Piece::Standard(StandardPieces::StandardKnight) => {}
+		Piece::Standard(piece) => query.p = Some(piece.code()),
+		Piece::Custom(custom) => {
+			query.p = Some("custom".to_string());
+			query.pn = Some(custom.name.clone());
+			query.pl = Some(encode_offsets(custom.piece.relative_moves()));
+			query.pr = Some(encode_offsets(custom.piece.rides()));
+		}
+	}
+	query
+}
+
+fn decode_board(query: Query) -> Result<SharedBoard, anyhow::Error> {
+	let version = query.v.context("Missing version")?;
+	if version != BOARD_VERSION {
+		anyhow::bail!("Unknown board version {}", version);
+	}
+	let width = query.w.context("Missing width")?;
+	let height = query.h.context("Missing height")?;
+	if width == 0 || height == 0 {
+		anyhow::bail!("Empty {}x{} board", width, height);
+	}
+	// checked before anything is allocated for the board, links can come from anyone
+	let max_size = Algorithm::all()
+		.iter()
+		.map(|alg| alg.max_board_size() as u16)
+		.max()
+		.unwrap_or(BoardOptions::MAX_SIZE as u16);
+	if width > max_size || height > max_size {
+		anyhow::bail!(
+			"The {}x{} board is larger than the largest supported, {}x{}",
+			width,
+			height,
+			max_size,
+			max_size
+		);
+	}
+	let cell_count = width as u32 * height as u32;
+	let point = |cell: u32| {
+		if cell >= cell_count {
+			anyhow::bail!("Cell {} is off the {}x{} board", cell, width, height);
+		}
+		Ok(ChessPoint::new(
+			(cell / width as u32) as u16 + 1,
+			(cell % width as u32) as u16 + 1,
+		))
+	};
+
+	let cells = decode_base64(query.c.as_deref().context("Missing cells")?)?;
+	if cells.len() < (cell_count as usize).div_ceil(4) {
+		anyhow::bail!(
+			"Only {} cells for a {}x{} board",
+			cells.len() * 4,
+			width,
+			height
+		);
+	}
+	let mut board_options = BoardOptions::new(width, height);
+	for (i, p) in all_points(width, height).enumerate() {
+		board_options.set_point(p, cell_option((cells[i / 4] >> (i % 4 * 2)) & 0b11));
+	}
+	board_options.set_topology(query.t.unwrap_or_default());
+
+	let recommended = decode_base64(query.r.as_deref().unwrap_or_default())?;
+	let mut reader = recommended.as_slice();
+	while !reader.is_empty() {
+		let from = point(read_varint(&mut reader)?)?;
+		let to = point(read_varint(&mut reader)?)?;
+		board_options.add_recommended_move(Move::new(from, to));
+	}
+
+	let piece = match query.p.as_deref() {
+		None => Piece::default(),
+		Some("custom") => {
+			let leaps = decode_offsets(query.pl.as_deref().unwrap_or_default())?;
+			let rides = decode_offsets(query.pr.as_deref().unwrap_or_default())?;
+			Piece::Custom(CustomPiece {
+				name: query.pn.unwrap_or_default(),
+				piece: ChessPiece::compound([ChessPiece::new(leaps), ChessPiece::rider(rides)]),
+			})
+		}
+		Some(code) => Piece::Standard(code.parse().map_err(anyhow::Error::msg)?),
+	};
+
+	let start = match query.s {
+		Some(start) => {
+			let (row, column) = start.split_once(',').context("Start is not `row,column`")?;
+			let start = ChessPoint::new(row.parse()?, column.parse()?);
+			if !board_options.validate_point(&start) {
+				anyhow::bail!("Start {} is off the board", start);
+			}
+			Some(start)
+		}
+		None => None,
+	};
+
+	let encoded_moves = decode_base64(query.m.as_deref().unwrap_or_default())?;
+	let mut reader = encoded_moves.as_slice();
+	let mut moves = Vec::new();
+	while !reader.is_empty() {
+		let from = point(read_varint(&mut reader)?)?;
+		let to = point(read_varint(&mut reader)?)?;
+		let (colour, rest) = reader.split_first().context("Move is missing its colour")?;
+		reader = rest;
+		moves.push((Move::new(from, to), *colour));
+	}
+
+	Ok(SharedBoard {
+		board_options,
+		piece,
+		start,
+		moves,
+	})
+}
+
+/// Row by row from the bottom left, the order cells are packed in
+fn all_points(width: u16, height: u16) -> impl Iterator<Item = ChessPoint> {
+	(1..=height).flat_map(move |row| (1..=width).map(move |column| ChessPoint::new(row, column)))
+}
+
+fn cell_bits(cell: CellOption) -> u8 {
+	match cell {
+		CellOption::Unavailable => 0,
+		CellOption::Available {
+			can_finish_on: true,
+		} => 1,
+		CellOption::Available {
+			can_finish_on: false,
+		} => 2,
+		CellOption::Eliminated => 3,
+	}
+}
+
+fn cell_option(bits: u8) -> CellOption {
+	match bits {
+		0 => CellOption::Unavailable,
+		1 => CellOption::Available {
+			can_finish_on: true,
+		},
+		2 => CellOption::Available {
+			can_finish_on: false,
+		},
+		_ => CellOption::Eliminated,
+	}
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, anyhow::Error> {
+	URL_SAFE_NO_PAD
+		.decode(encoded)
+		.with_context(|| format!("{:?} is not base64", encoded))
+}
+
+/// 7 bits per byte, least significant first, so small numbers take a single byte
+fn write_varint(bytes: &mut Vec<u8>, mut n: u32) {
+	while n >= 0x80 {
+		bytes.push(n as u8 | 0x80);
+		n >>= 7;
+	}
+	bytes.push(n as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u32, anyhow::Error> {
+	let mut n: u32 = 0;
+	for shift in (0..32).step_by(7) {
+		let (byte, rest) = bytes.split_first().context("Number cut off")?;
+		*bytes = rest;
+		n |= ((byte & 0x7f) as u32) << shift;
+		if byte & 0x80 == 0 {
+			return Ok(n);
+		}
+	}
+	anyhow::bail!("Number too large")
+}
+
+/// Signs are zigzagged, so offsets close to 0 take a single byte
+fn encode_offsets(offsets: &[(i16, i16)]) -> String {
+	let zigzag = |n: i16| ((n << 1) ^ (n >> 15)) as u16 as u32;
+	let mut bytes = Vec::new();
+	for (dx, dy) in offsets {
+		write_varint(&mut bytes, zigzag(*dx));
+		write_varint(&mut bytes, zigzag(*dy));
+	}
+	URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn decode_offsets(encoded: &str) -> Result<Vec<(i16, i16)>, anyhow::Error> {
+	let unzigzag = |n: u32| ((n >> 1) as i16) ^ -((n & 1) as i16);
+	let bytes = decode_base64(encoded)?;
+	let mut reader = bytes.as_slice();
+	let mut offsets = Vec::new();
+	while !reader.is_empty() {
+		let dx = unzigzag(read_varint(&mut reader)?);
+		let dy = unzigzag(read_varint(&mut reader)?);
+		offsets.push((dx, dy));
+	}
+	Ok(offsets)
+}
+
 #[test]
 fn test_extract_url() {
 	let test_url = "http://0.0.0.0:6969/?id=xZET4CwfRm2zd";
-	let id = extract_url(test_url.into());
-	assert_eq!(id, Some("xZET4CwfRm2zd".to_string()));
+	let id = extract_url(test_url);
+	assert_eq!(id, Some(UrlData::Id("xZET4CwfRm2zd".to_string())));
+	assert_eq!(extract_url("http://0.0.0.0:6969/"), None);
+}
+
+#[test]
+fn boards_survive_a_link() {
+	let mut board_options = BoardOptions::new(7, 5);
+	board_options.rm((2, 3));
+	board_options.eliminate(&ChessPoint::new(5, 7));
+	board_options.set_point(
+		(1, 7),
+		CellOption::Available {
+			can_finish_on: false,
+		},
+	);
+	board_options.add_recommended_move(Move::new(ChessPoint::new(1, 1), ChessPoint::new(3, 2)));
+	board_options.set_topology(Topology::Mobius);
+	let moves = vec![
+		(Move::new(ChessPoint::new(1, 1), ChessPoint::new(1, 1)), 0),
+		(Move::new(ChessPoint::new(1, 1), ChessPoint::new(5, 7)), 4),
+	];
+
+	let pieces = [
+		Piece::default(),
+		Piece::Standard(StandardPieces::Nightrider),
+		Piece::Standard(StandardPieces::ABKnight(-2, 5)),
+		Piece::Custom(CustomPiece {
+			name: "Wide & tall?".to_string(),
+			piece: ChessPiece::compound([
+				ChessPiece::new(vec![(1, -200), (0, 3)]),
+				ChessPiece::rider(vec![(-1, -1)]),
+			]),
+		}),
+	];
+	for piece in pieces {
+		let board = SharedBoard {
+			board_options: board_options.clone(),
+			piece,
+			start: Some(ChessPoint::new(4, 6)),
+			moves: moves.clone(),
+		};
+		let url = create_url_with_board(&board);
+		assert!(url.len() < 200, "{} is too long", url);
+		assert_eq!(extract_url(&url), Some(UrlData::Board(board)), "{}", url);
+	}
+
+	// a full 8x8 board with nothing else is tiny
+	let board = SharedBoard {
+		board_options: BoardOptions::new(8, 8),
+		piece: Piece::default(),
+		start: None,
+		moves: Vec::new(),
+	};
+	assert_eq!(
+		create_url_with_board(&board),
+		format!("{}?v=1&w=8&h=8&c=VVVVVVVVVVVVVVVVVVVVVQ", URL)
+	);
+
+	let truncated = format!("{}?v=1&w=8&h=8&c=VVVV", URL);
+	assert_eq!(extract_url(&truncated), None);
+	let off_board = format!("{}?v=1&w=2&h=2&c=VQ&s=3,1", URL);
+	assert_eq!(extract_url(&off_board), None);
+	let huge = format!("{}?v=1&w=65535&h=65535&c=AA", URL);
+	assert_eq!(extract_url(&huge), None);
 }